@@ -4,10 +4,12 @@
 var texture: texture_2d<f32>;
 @group(1) @binding(1)
 var texture_sampler: sampler;
+@group(1) @binding(2)
+var<uniform> tint: vec4<f32>;
 
 @fragment
 fn fragment(
     mesh: VertexOutput,
 ) -> @location(0) vec4<f32> {
-    return textureSample(texture, texture_sampler, mesh.uv);
+    return textureSample(texture, texture_sampler, mesh.uv) * tint;
 }
\ No newline at end of file
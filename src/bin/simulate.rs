@@ -0,0 +1,101 @@
+// Headless simulation for balance testing. Plays games with the autopilot, without a window,
+// as fast as the computer allows, and prints statistics for each game and the average over all of them.
+//
+// cargo run --release --bin simulate -- --games 100 --seconds 300 --seed 0
+//
+// Every game uses the next seed, so a game worth a closer look can be played with "cargo run -- --seed N".
+// With "--replay FILE" the recorded controls are used instead of the autopilot.
+
+use bevy::{
+    prelude::*,
+    time::TimeUpdateStrategy,
+};
+use std::time::Duration;
+
+use cometbuster::{
+    consts::*,
+    helpers::arg_value,
+    c_appstate::AppState,
+    c_gamerng::GameRng,
+    c_lifetime_spawntime::SimTick,
+    c_sim_stats::SimStats,
+    c_levels::Wave,
+    s_autopilot::AutopilotPlugin,
+    s_gameplay::GameplayPlugin,
+    s_replay::ReplayPlugin,
+    s_sim_stats::SimStatsPlugin,
+};
+
+fn main() {
+    let games: u64 = arg_value("--games").and_then(|games| games.parse().ok()).unwrap_or(10);
+    let seconds: u64 = arg_value("--seconds").and_then(|seconds| seconds.parse().ok()).unwrap_or(300);
+    let first_seed: u64 = arg_value("--seed").and_then(|seed| seed.parse().ok()).unwrap_or(0);
+
+    let mut all_stats = Vec::<SimStats>::new();
+    for game in 0..games {
+        let seed = first_seed + game;
+        let stats = simulate_game(seed, seconds * TICKS_PER_SECOND);
+        println!("Seed {:>6}: {}", seed, describe(&stats));
+        all_stats.push(stats);
+    }
+
+    if all_stats.is_empty() { return; }
+    let count = all_stats.len() as f32;
+    let average = |value: fn(&SimStats) -> f32| all_stats.iter().map(value).sum::<f32>() / count;
+    println!();
+    println!("Average over {} games:", all_stats.len());
+    println!("  Time survived:      {:.1} s", average(|stats| stats.seconds_survived()));
+    println!("  Game over:          {:.0} %", 100.0 * average(|stats| if stats.game_over { 1.0 } else { 0.0 }));
+    println!("  Lives lost:         {:.2}", average(|stats| stats.lives_lost as f32));
+    println!("  Waves cleared:      {:.2}", average(|stats| stats.waves_cleared as f32));
+    println!("  Asteroids big:      {:.2}", average(|stats| stats.big_asteroids_destroyed as f32));
+    println!("  Asteroids medium:   {:.2}", average(|stats| stats.medium_asteroids_destroyed as f32));
+    println!("  Asteroids small:    {:.2}", average(|stats| stats.small_asteroids_destroyed as f32));
+    println!("  Shots fired:        {:.1}", average(|stats| stats.shots_fired as f32));
+    println!("  Shield uptime:      {:.1} %", 100.0 * average(|stats| stats.shield_uptime()));
+    println!("  Hardest collision:  {:.0}", average(|stats| stats.strongest_collision));
+}
+
+// Runs one game until the last life is lost or the time runs out
+fn simulate_game(seed: u64, max_ticks: u64) -> SimStats {
+    let mut app = App::new();
+    app
+    .add_plugins(MinimalPlugins)
+    // Every update advances time by exactly one tick
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / TICKS_PER_SECOND as f64)))
+    .insert_resource(GameRng::from_seed(seed))
+    .add_plugins(ReplayPlugin)
+    .add_plugins(GameplayPlugin)
+    .add_plugins(AutopilotPlugin)
+    .add_plugins(SimStatsPlugin)
+    .add_systems(Startup, |mut next_state: ResMut<NextState<AppState>>| next_state.set(AppState::SpawnStart))
+    ;
+    app.finish();
+    app.cleanup();
+
+    loop {
+        app.update();
+        let game_over = *app.world.resource::<State<AppState>>().get() == AppState::GameOver;
+        if game_over || app.world.resource::<SimTick>().0 >= max_ticks {
+            let mut stats = app.world.resource::<SimStats>().clone();
+            stats.game_over = game_over;
+            stats.waves_cleared = app.world.resource::<Wave>().number - 1;
+            return stats;
+        }
+    }
+}
+
+fn describe(stats: &SimStats) -> String {
+    format!(
+        "{:6.1} s {}, {:>2} lives lost, {:>2} waves cleared, asteroids destroyed {:>2} big {:>2} medium {:>2} small, {:>4} shots, shield up {:4.1} %",
+        stats.seconds_survived(),
+        if stats.game_over { "until game over" } else { "and survived   " },
+        stats.lives_lost,
+        stats.waves_cleared,
+        stats.big_asteroids_destroyed,
+        stats.medium_asteroids_destroyed,
+        stats.small_asteroids_destroyed,
+        stats.shots_fired,
+        100.0 * stats.shield_uptime(),
+    )
+}
\ No newline at end of file
@@ -1,10 +1,19 @@
 use bevy::ecs::schedule::States;
+use bevy::ecs::system::Resource;
 
 #[derive(States, PartialEq, Eq, Debug, Clone, Hash, Default)]
 pub enum AppState {
     #[default]
-    SetupMaterials,
+    Loading,
     SpawnStart,
     InGame,
+    WaveTransition,
     Paused,
-}
\ No newline at end of file
+    GameOver,
+    MainMenu,
+    Options,
+}
+
+// The state the game was paused in, for Resume to go back to
+#[derive(Resource)]
+pub struct PausedFrom(pub AppState);
\ No newline at end of file
@@ -1,4 +1,5 @@
 use bevy::prelude::*;
+use crate::consts::*;
 use crate::c_movement_and_collisions::*;
 use crate::c_tags::*;
 use crate::c_shipstats::*;
@@ -6,6 +7,8 @@ use crate::c_sprites::*;
 use crate::c_chargelevel::*;
 use crate::c_lifetime_spawntime::*;
 use crate::c_events::*;
+use crate::c_controls::ControlState;
+use crate::c_players::PlayerId;
 
 #[derive(Bundle)]
 pub struct PhysicsObjectBundle {
@@ -17,6 +20,8 @@ pub struct PhysicsObjectBundle {
     pub angle: Angle,
     pub mass: Mass,
     pub radius: Radius,
+    pub restitution: Restitution,
+    pub friction: Friction,
     pub visibility: Visibility,
     pub inherited_visibility: InheritedVisibility,
 }
@@ -31,6 +36,8 @@ impl Default for PhysicsObjectBundle {
             angle: Angle::default(),
             mass: Mass::default(),
             radius: Radius::default(),
+            restitution: Restitution::default(),
+            friction: Friction::default(),
             visibility: Visibility::Visible,
             inherited_visibility: InheritedVisibility::default(),
         }
@@ -40,10 +47,12 @@ impl Default for PhysicsObjectBundle {
 #[derive(Bundle)]
 pub struct ShipBundle {
     pub player: Player,
+    pub player_id: PlayerId,
     pub collision_type: CollisionType,
     pub sprite_type: SpriteType,
     pub physics_object: PhysicsObjectBundle,
     pub ship_stats: ShipStats,
+    pub control_state: ControlState,
     pub energy: Energy,
     pub charge_level: ChargeLevel,
 }
@@ -51,6 +60,7 @@ impl Default for ShipBundle {
     fn default() -> Self {
         Self {
             player: Player,
+            player_id: PlayerId::default(),
             collision_type: CollisionType::Ship,
             sprite_type: SpriteType::Ship,
             physics_object: PhysicsObjectBundle {
@@ -63,11 +73,21 @@ impl Default for ShipBundle {
                 ..Default::default()
             },
             ship_stats: ShipStats::default(),
+            control_state: ControlState::default(),
             energy: Energy::default(),
             charge_level: ChargeLevel::default(),
         }
     }
 }
+impl ShipBundle {
+    // A ship of one of the local players, steered by the bindings of that player
+    pub fn for_player(player_id: PlayerId) -> Self {
+        Self {
+            player_id,
+            ..Default::default()
+        }
+    }
+}
 
 #[derive(Bundle)]
 pub struct BulletBundle {
@@ -94,8 +114,8 @@ impl Default for BulletBundle {
                 radius: Radius(4.0),
                 ..Default::default()
             },
-            spawn_time: SpawnTime(instant::Instant::now()),
-            lifetime: Lifetime(instant::Duration::new(1, 0)),
+            spawn_time: SpawnTime(0),
+            lifetime: Lifetime::from_seconds(1.0),
             charge_level: ChargeLevel::default(),
         }
     }
@@ -106,6 +126,7 @@ pub struct AsteroidBigBundle {
     pub asteroid_size: AsteroidSize,
     pub collision_type: CollisionType,
     pub sprite_type: SpriteType,
+    pub angular_velocity: AngularVelocity,
     pub physics_object: PhysicsObjectBundle,
 }
 impl Default for AsteroidBigBundle {
@@ -113,7 +134,8 @@ impl Default for AsteroidBigBundle {
         Self {
             asteroid_size: AsteroidSize::Big,
             collision_type: CollisionType::Asteroid,
-            sprite_type: SpriteType::Asteroid1,
+            sprite_type: SpriteType::Asteroid,
+            angular_velocity: AngularVelocity::default(),
             physics_object: PhysicsObjectBundle {
                 transform: Transform {
                     translation: Vec3::new(0.0, 0.0, 10.0),
@@ -121,6 +143,7 @@ impl Default for AsteroidBigBundle {
                 },
                 mass: Mass(100.0),
                 radius: Radius(88.0),
+                friction: Friction(ASTEROID_FRICTION),
                 ..Default::default()
             }
         }
@@ -132,6 +155,7 @@ pub struct AsteroidMediumBundle {
     pub asteroid_size: AsteroidSize,
     pub collision_type: CollisionType,
     pub sprite_type: SpriteType,
+    pub angular_velocity: AngularVelocity,
     pub physics_object: PhysicsObjectBundle,
 }
 impl Default for AsteroidMediumBundle {
@@ -139,7 +163,8 @@ impl Default for AsteroidMediumBundle {
         Self {
             asteroid_size: AsteroidSize::Medium,
             collision_type: CollisionType::Asteroid,
-            sprite_type: SpriteType::Asteroid1,
+            sprite_type: SpriteType::Asteroid,
+            angular_velocity: AngularVelocity::default(),
             physics_object: PhysicsObjectBundle {
                 transform: Transform {
                     translation: Vec3::new(0.0, 0.0, 10.0),
@@ -147,6 +172,7 @@ impl Default for AsteroidMediumBundle {
                 },
                 mass: Mass(50.0),
                 radius: Radius(39.0),
+                friction: Friction(ASTEROID_FRICTION),
                 ..Default::default()
             }
         }
@@ -158,6 +184,7 @@ pub struct AsteroidSmallBundle {
     pub asteroid_size: AsteroidSize,
     pub collision_type: CollisionType,
     pub sprite_type: SpriteType,
+    pub angular_velocity: AngularVelocity,
     pub physics_object: PhysicsObjectBundle,
 }
 impl Default for AsteroidSmallBundle {
@@ -165,7 +192,8 @@ impl Default for AsteroidSmallBundle {
         Self {
             asteroid_size: AsteroidSize::Small,
             collision_type: CollisionType::Asteroid,
-            sprite_type: SpriteType::Asteroid1,
+            sprite_type: SpriteType::Asteroid,
+            angular_velocity: AngularVelocity::default(),
             physics_object: PhysicsObjectBundle {
                 transform: Transform {
                     translation: Vec3::new(0.0, 0.0, 10.0),
@@ -173,6 +201,7 @@ impl Default for AsteroidSmallBundle {
                 },
                 mass: Mass(20.0),
                 radius: Radius(17.0),
+                friction: Friction(ASTEROID_FRICTION),
                 ..Default::default()
             }
         }
@@ -0,0 +1,178 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use crate::consts::*;
+use crate::c_chargelevel::ChargeLevel;
+use crate::c_lifetime_spawntime::{SimTick, SpawnTime};
+use crate::c_movement_and_collisions::CollisionType;
+use crate::c_sprites::AsteroidSize;
+
+// Which of the two colliding objects of a rule a condition or response refers to
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq)]
+pub enum Side {
+    A,
+    B,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum CollisionCondition {
+    Size(Side, AsteroidSize),
+    ChargeAtLeast(Side, f32),
+    ChargeAbove(Side, f32),
+    OlderThan(Side, f32), // Seconds since spawn
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub enum CollisionRuleEvent {
+    AsteroidFragments(Side), // Side is the asteroid that breaks up
+    ShieldCollision(Side), // Side is the ship holding the shield
+    Kill(Side), // Side is the bullet, credited to the player who fired it. The other side is the ship.
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub enum CollisionResponse {
+    Ignore,
+    Bounce,
+    Destroy(Side),
+    Damage(Side, f32), // Drains energy
+    Emit(CollisionRuleEvent),
+}
+
+// A list of responses, applied when all conditions hold
+#[derive(Clone, Debug, Deserialize)]
+pub struct CollisionOutcome {
+    #[serde(default)]
+    pub when: Vec<CollisionCondition>,
+    pub then: Vec<CollisionResponse>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct CollisionRule {
+    pub a: CollisionType,
+    pub b: CollisionType,
+    pub outcomes: Vec<CollisionOutcome>,
+}
+
+#[derive(Deserialize)]
+struct CollisionRulesFile {
+    rules: Vec<CollisionRule>,
+}
+
+// Maps each pair of collision types to its outcomes. Pairs without a rule ignore each other.
+#[derive(Resource)]
+pub struct CollisionRules {
+    pub rules: HashMap<(CollisionType, CollisionType), Vec<CollisionOutcome>>,
+}
+
+impl Default for CollisionRules {
+    fn default() -> Self {
+        Self::from_ron(include_str!("../assets/collision_rules.ron")).unwrap()
+    }
+}
+
+impl CollisionRules {
+    // Each pair may only have one rule, in either order, so that no rule silently replaces another
+    pub fn from_ron(ron_string: &str) -> Result<Self, String> {
+        let file: CollisionRulesFile = ron::from_str(ron_string).map_err(|error| error.to_string())?;
+        let mut rules = HashMap::new();
+        for rule in file.rules {
+            if rules.contains_key(&(rule.a, rule.b)) {
+                return Err(format!("More than one rule for ({:?}, {:?})", rule.a, rule.b));
+            }
+            if rules.contains_key(&(rule.b, rule.a)) {
+                return Err(format!("Rule for ({:?}, {:?}) repeats the rule for ({:?}, {:?})", rule.a, rule.b, rule.b, rule.a));
+            }
+            rules.insert((rule.a, rule.b), rule.outcomes);
+        }
+        Ok(Self { rules })
+    }
+
+    // Reads the rules from the assets folder, so they can be changed without recompiling.
+    // Falls back to the rules built into the binary if the file is missing or broken.
+    pub fn load() -> Self {
+        match std::fs::read_to_string(format!("assets/{}", COLLISION_RULES_FILE)) {
+            Ok(ron_string) => match Self::from_ron(&ron_string) {
+                Ok(rules) => rules,
+                Err(error) => {
+                    error!("Could not parse {}: {}", COLLISION_RULES_FILE, error);
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    // Returns the outcomes for a pair, and whether the pair matched the rule in reverse order (entity 1 is B)
+    pub fn get(&self, type_1: CollisionType, type_2: CollisionType) -> Option<(&Vec<CollisionOutcome>, bool)> {
+        if let Some(outcomes) = self.rules.get(&(type_1, type_2)) {
+            return Some((outcomes, false));
+        }
+        self.rules.get(&(type_2, type_1)).map(|outcomes| (outcomes, true))
+    }
+
+    // Every charge level a rule checks for, in order, each listed once
+    pub fn charge_thresholds(&self) -> Vec<f32> {
+        let mut thresholds: Vec<f32> = self.rules.values()
+            .flatten()
+            .flat_map(|outcome| outcome.when.iter())
+            .filter_map(|condition| match condition {
+                CollisionCondition::ChargeAtLeast(_, threshold) | CollisionCondition::ChargeAbove(_, threshold) => Some(*threshold),
+                _ => None,
+            })
+            .collect();
+        thresholds.sort_by(f32::total_cmp);
+        thresholds.dedup();
+        thresholds
+    }
+}
+
+// The components of a colliding object that rule conditions can look at
+pub struct CollisionBody<'a> {
+    pub asteroid_size: Option<&'a AsteroidSize>,
+    pub charge_level: Option<&'a ChargeLevel>,
+    pub spawn_time: Option<&'a SpawnTime>,
+}
+
+impl CollisionCondition {
+    pub fn holds(&self, body_a: &CollisionBody, body_b: &CollisionBody, sim_tick: &SimTick) -> bool {
+        let body = |side: &Side| if *side == Side::A { body_a } else { body_b };
+        match self {
+            CollisionCondition::Size(side, size) => body(side).asteroid_size.is_some_and(|asteroid_size| asteroid_size == size),
+            CollisionCondition::ChargeAtLeast(side, threshold) => body(side).charge_level.is_some_and(|charge_level| charge_level.0 >= *threshold),
+            CollisionCondition::ChargeAbove(side, threshold) => body(side).charge_level.is_some_and(|charge_level| charge_level.0 > *threshold),
+            CollisionCondition::OlderThan(side, seconds) => body(side).spawn_time.is_some_and(|spawn_time| spawn_time.age_seconds(sim_tick) > *seconds),
+        }
+    }
+}
+
+impl CollisionOutcome {
+    pub fn applies(&self, body_a: &CollisionBody, body_b: &CollisionBody, sim_tick: &SimTick) -> bool {
+        self.when.iter().all(|condition| condition.holds(body_a, body_b, sim_tick))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pairs_with_more_than_one_rule_are_refused() {
+        let duplicate = "(rules: [(a: Bullet, b: Asteroid, outcomes: []), (a: Bullet, b: Asteroid, outcomes: [])])";
+        let mirrored = "(rules: [(a: Bullet, b: Asteroid, outcomes: []), (a: Asteroid, b: Bullet, outcomes: [])])";
+        assert!(CollisionRules::from_ron(duplicate).is_err());
+        assert!(CollisionRules::from_ron(mirrored).is_err());
+        assert!(CollisionRules::from_ron("(rules: [(a: Asteroid, b: Asteroid, outcomes: []), (a: Bullet, b: Asteroid, outcomes: [])])").is_ok());
+    }
+
+    #[test]
+    fn charge_thresholds_come_from_the_conditions() {
+        let rules = CollisionRules::from_ron("(rules: [
+            (a: Bullet, b: Asteroid, outcomes: [
+                (when: [ChargeAtLeast(A, 2.0)], then: []),
+                (when: [Size(B, Medium), ChargeAtLeast(A, 1.0)], then: []),
+            ]),
+            (a: Bullet, b: Ship, outcomes: [(when: [ChargeAbove(A, 1.0)], then: [])]),
+        ])").unwrap();
+        assert_eq!(rules.charge_thresholds(), vec![1.0, 2.0]);
+    }
+}
\ No newline at end of file
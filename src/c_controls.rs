@@ -1,21 +1,185 @@
 use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::consts::*;
 
+// What a player can do with a ship. Which keys, buttons and sticks do it is up to the Controls of the player.
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Hash, Serialize)]
+pub enum Action {
+    Accelerate,
+    TurnLeft,
+    TurnRight,
+    Fire,
+    Shield,
+}
+impl Action {
+    pub const ALL: [Action; 5] = [Action::Accelerate, Action::TurnLeft, Action::TurnRight, Action::Fire, Action::Shield];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Accelerate => "Accelerate",
+            Action::TurnLeft => "Turn left",
+            Action::TurnRight => "Turn right",
+            Action::Fire => "Fire",
+            Action::Shield => "Shield",
+        }
+    }
+}
+
+// Something on the keyboard or a gamepad that triggers an action while held
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Serialize)]
+pub enum Binding {
+    Key(KeyCode),
+    Button(GamepadButtonType),
+    Axis(GamepadAxisType, f32), // Held when pushed past the dead zone towards the sign, like a trigger or a stick pushed up
+}
+impl Binding {
+    pub fn pressed(&self, gamepad: Gamepad, keyboard: &Input<KeyCode>, buttons: &Input<GamepadButton>, axes: &Axis<GamepadAxis>) -> bool {
+        match self {
+            Binding::Key(key) => keyboard.pressed(*key),
+            Binding::Button(button) => buttons.pressed(GamepadButton::new(gamepad, *button)),
+            Binding::Axis(axis, sign) => axes.get(GamepadAxis::new(gamepad, *axis))
+                .is_some_and(|value| value * sign.signum() > GAMEPAD_DEAD_ZONE),
+        }
+    }
+
+    pub fn is_key(&self) -> bool {
+        matches!(self, Binding::Key(_))
+    }
+
+    pub fn name(&self) -> String {
+        match self {
+            Binding::Key(key) => format!("{:?}", key),
+            Binding::Button(button) => format!("Pad {:?}", button),
+            Binding::Axis(axis, sign) => format!("Pad {:?}{}", axis, if *sign < 0.0 { "-" } else { "+" }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Deserialize, PartialEq, Eq, Serialize)]
+pub enum Stick {
+    Left,
+    Right,
+}
+impl Stick {
+    // The direction the stick points in, in radians, or None while it rests in the dead zone
+    pub fn direction(&self, gamepad: Gamepad, axes: &Axis<GamepadAxis>) -> Option<f32> {
+        let (axis_x, axis_y) = match self {
+            Stick::Left => (GamepadAxisType::LeftStickX, GamepadAxisType::LeftStickY),
+            Stick::Right => (GamepadAxisType::RightStickX, GamepadAxisType::RightStickY),
+        };
+        let stick = Vec2::new(
+            axes.get(GamepadAxis::new(gamepad, axis_x))?,
+            axes.get(GamepadAxis::new(gamepad, axis_y))?,
+        );
+        (stick.length() > GAMEPAD_DEAD_ZONE).then(|| stick.y.atan2(stick.x))
+    }
+}
+
+// The binding profile of one player. The steering stick turns the ship to face the way it points, instead of turning left or right.
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
 pub struct Controls {
-    pub accelerate: KeyCode,
-    pub turn_left: KeyCode,
-    pub turn_right: KeyCode,
-    pub fire: KeyCode,
-    pub shield: KeyCode,
+    pub bindings: Vec<(Action, Binding)>,
+    pub steering: Option<Stick>,
+    pub gamepad: usize, // Gamepads are numbered from 0 in the order they were connected
 }
 
 impl Default for Controls {
     fn default() -> Self {
+        Self::new(0, [
+            (Action::Accelerate, KeyCode::Up),
+            (Action::TurnLeft, KeyCode::Left),
+            (Action::TurnRight, KeyCode::Right),
+            (Action::Fire, KeyCode::X),
+            (Action::Shield, KeyCode::Z),
+        ])
+    }
+}
+
+impl Controls {
+    // The given keys, plus the same gamepad layout for every player
+    pub fn new(gamepad: usize, keys: [(Action, KeyCode); 5]) -> Self {
+        let mut bindings: Vec<(Action, Binding)> = keys.iter().map(|(action, key)| (*action, Binding::Key(*key))).collect();
+        bindings.extend([
+            (Action::Accelerate, Binding::Button(GamepadButtonType::RightTrigger2)),
+            (Action::TurnLeft, Binding::Button(GamepadButtonType::DPadLeft)),
+            (Action::TurnRight, Binding::Button(GamepadButtonType::DPadRight)),
+            (Action::Fire, Binding::Button(GamepadButtonType::South)),
+            (Action::Shield, Binding::Button(GamepadButtonType::East)),
+            (Action::Shield, Binding::Button(GamepadButtonType::LeftTrigger2)),
+        ]);
         Self {
-            accelerate: KeyCode::Up,
-            turn_left: KeyCode::Left,
-            turn_right: KeyCode::Right,
-            fire: KeyCode::X,
-            shield: KeyCode::Z,
+            bindings,
+            steering: Some(Stick::Left),
+            gamepad,
+        }
+    }
+
+    pub fn frame(&self, keyboard: &Input<KeyCode>, buttons: &Input<GamepadButton>, axes: &Axis<GamepadAxis>) -> ControlFrame {
+        let gamepad = Gamepad::new(self.gamepad);
+        let mut frame = ControlFrame::default();
+        for (action, binding) in self.bindings.iter() {
+            if binding.pressed(gamepad, keyboard, buttons, axes) {
+                frame.press(*action);
+            }
         }
+        frame.steer = self.steering.and_then(|stick| stick.direction(gamepad, axes));
+        frame
+    }
+
+    // Replaces the keys of the action with a key, or its gamepad bindings with a button or axis
+    pub fn rebind(&mut self, action: Action, binding: Binding) {
+        self.bindings.retain(|(bound_action, bound)| *bound_action != action || bound.is_key() != binding.is_key());
+        self.bindings.push((action, binding));
+    }
+
+    pub fn bindings_of(&self, action: Action) -> impl Iterator<Item = &Binding> {
+        self.bindings.iter().filter(move |(bound_action, _)| *bound_action == action).map(|(_, binding)| binding)
+    }
+}
+
+// State of each action on one simulation tick. This is all the ship is steered by, so tests and the autopilot fill it in without a device.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Serialize)]
+pub struct ControlFrame {
+    pub accelerate: bool,
+    pub turn_left: bool,
+    pub turn_right: bool,
+    pub fire: bool,
+    pub shield: bool,
+    // Direction to turn the ship to, in radians, from a steering stick. Left out of replays while unused.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub steer: Option<f32>,
+}
+impl ControlFrame {
+    pub fn pressed(&self, action: Action) -> bool {
+        match action {
+            Action::Accelerate => self.accelerate,
+            Action::TurnLeft => self.turn_left,
+            Action::TurnRight => self.turn_right,
+            Action::Fire => self.fire,
+            Action::Shield => self.shield,
+        }
+    }
+
+    pub fn press(&mut self, action: Action) {
+        match action {
+            Action::Accelerate => self.accelerate = true,
+            Action::TurnLeft => self.turn_left = true,
+            Action::TurnRight => self.turn_right = true,
+            Action::Fire => self.fire = true,
+            Action::Shield => self.shield = true,
+        }
+    }
+}
+
+// Controls of a ship on the current and the previous tick, so presses and releases are seen exactly once
+#[derive(Component, Default)]
+pub struct ControlState {
+    pub current: ControlFrame,
+    pub previous: ControlFrame,
+}
+impl ControlState {
+    pub fn advance(&mut self, frame: ControlFrame) {
+        self.previous = self.current;
+        self.current = frame;
     }
 }
\ No newline at end of file
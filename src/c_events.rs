@@ -1,19 +1,67 @@
 use bevy::prelude::*;
-use crate::c_movement_and_collisions::Velocity;
+use crate::c_movement_and_collisions::{ConvexHull, Velocity};
 use crate::c_sprites::AsteroidSize;
 
 #[derive(Component, Event)]
 pub struct EvSpawnAsteroidFragments{
     pub transform: Transform,
     pub velocity: Velocity,
-    pub asteroid_size_destroyed: AsteroidSize
+    pub asteroid_size_destroyed: AsteroidSize,
+    pub hull: Option<ConvexHull>, // The outline the fragments are cut from, turned by angle
+    pub angle: f32,
 }
 
 #[derive(Component, Event)]
 pub struct EvShieldCollision{
+    pub ship: Entity, // Holding the shield
     pub shield_position: Vec2,
     pub other_position: Vec2,
 }
 
+// A bullet destroyed a ship
+#[derive(Event)]
+pub struct EvKill{
+    pub bullet: Entity,
+    pub ship: Entity,
+}
+
 #[derive(Component, Event)]
-pub struct EvCmpSpawnSprites;
\ No newline at end of file
+pub struct EvCmpSpawnSprites;
+
+// Published by collision detection for every pair of touching objects. The normal points from A to B.
+#[derive(Clone, Copy, Event)]
+pub struct EvCollision{
+    pub a: Entity,
+    pub b: Entity,
+    pub normal: Vec2,
+    pub contact_point: Vec2,
+    pub penetration: f32, // Overlap along the normal
+    pub impulse: Vec2, // Impulse on B if the collision was perfectly elastic
+}
+
+#[derive(Event)]
+pub struct EvDestroy{
+    pub entity: Entity,
+}
+
+#[derive(Event)]
+pub struct EvDamage{
+    pub entity: Entity,
+    pub amount: f32,
+}
+
+#[derive(Event)]
+pub struct EvBounce{
+    pub collision: EvCollision,
+}
+
+#[derive(Event)]
+pub struct EvSpawnBounceEffect{
+    pub contact_point: Vec2,
+    pub change_of_momentum: f32,
+}
+
+
+// Clears the field and spawns the current wave again
+#[derive(Event)]
+pub struct EvRestartWave;
\ No newline at end of file
@@ -0,0 +1,68 @@
+use bevy::prelude::*;
+use rand::SeedableRng;
+use rand_chacha::ChaCha8Rng;
+use serde::Deserialize;
+use crate::consts::*;
+use crate::helpers::arg_value;
+
+// Seeded random number generators. ChaCha8 gives the same sequence on every platform, unlike thread_rng.
+// Gameplay and cosmetics draw from separate streams of the same seed,
+// so effects like screen shake can never change the outcome of a game.
+#[derive(Resource)]
+pub struct GameRng {
+    pub seed: u64,
+    pub gameplay: ChaCha8Rng,
+    pub cosmetic: ChaCha8Rng,
+}
+
+impl GameRng {
+    pub fn from_seed(seed: u64) -> Self {
+        let mut cosmetic = ChaCha8Rng::seed_from_u64(seed);
+        cosmetic.set_stream(1);
+        Self {
+            seed,
+            gameplay: ChaCha8Rng::seed_from_u64(seed),
+            cosmetic,
+        }
+    }
+}
+
+// Seed from --seed on the command line, then from the config file, otherwise a new random one
+impl Default for GameRng {
+    fn default() -> Self {
+        let seed = seed_from_args()
+        .or_else(seed_from_config)
+        .unwrap_or_else(rand::random::<u64>);
+        info!("Random seed: {}", seed);
+        Self::from_seed(seed)
+    }
+}
+
+fn seed_from_args() -> Option<u64> {
+    if !std::env::args().any(|arg| arg == "--seed") {
+        return None;
+    }
+    match arg_value("--seed").map(|seed| seed.parse::<u64>()) {
+        Some(Ok(seed)) => Some(seed),
+        _ => {
+            error!("--seed needs a positive whole number");
+            None
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct RngConfig {
+    seed: Option<u64>,
+}
+
+fn seed_from_config() -> Option<u64> {
+    let ron_string = std::fs::read_to_string(format!("assets/{}", RNG_CONFIG_FILE)).ok()?;
+    match ron::from_str::<RngConfig>(&ron_string) {
+        Ok(config) => config.seed,
+        Err(error) => {
+            error!("Could not parse {}: {}", RNG_CONFIG_FILE, error);
+            None
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,14 @@
+use bevy::prelude::*;
+use crate::c_players::PlayerId;
+
+// The filled part of the energy bar of a player. Follows whichever ship the player has in play.
+#[derive(Component)]
+pub struct HudEnergyFill(pub PlayerId);
+
+// The filled part of the charge meter of a player
+#[derive(Component)]
+pub struct HudChargeFill(pub PlayerId);
+
+// Score, lives and wave
+#[derive(Component)]
+pub struct HudStatusText;
\ No newline at end of file
@@ -0,0 +1,227 @@
+use bevy::{
+    prelude::*,
+    asset::{io::Reader, AssetLoader, AsyncReadExt, LoadContext},
+    utils::BoxedFuture,
+};
+use serde::Deserialize;
+use crate::consts::*;
+use crate::c_playfield::Playfield;
+use crate::c_shipstats::ShipStats;
+use crate::c_sprites::AsteroidSize;
+
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+pub enum AsteroidKind {
+    #[default]
+    Normal,
+    Dense, // Heavier than normal asteroids of the same size
+}
+
+// An asteroid placed exactly where the level designer put it
+#[derive(Clone, Debug, Deserialize)]
+pub struct AsteroidPlacement {
+    pub size: AsteroidSize,
+    pub position: (f32, f32),
+    #[serde(default)]
+    pub velocity: (f32, f32),
+    #[serde(default)]
+    pub kind: AsteroidKind,
+}
+
+// A number of asteroids placed at random free positions
+#[derive(Clone, Debug, Deserialize)]
+pub struct AsteroidGroup {
+    pub size: AsteroidSize,
+    pub count: u32,
+    #[serde(default)]
+    pub kind: AsteroidKind,
+}
+
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct ShipStatsOverride {
+    pub acceleration: Option<f32>,
+    pub turn_rate: Option<f32>,
+    pub charge_rate: Option<f32>,
+    pub bullet_speed: Option<f32>,
+    pub shield_regeneration: Option<f32>,
+}
+impl ShipStatsOverride {
+    pub fn apply(&self, ship_stats: &mut ShipStats) {
+        let defaults = ShipStats::default();
+        ship_stats.acceleration = self.acceleration.unwrap_or(defaults.acceleration);
+        ship_stats.turn_rate = self.turn_rate.unwrap_or(defaults.turn_rate);
+        ship_stats.charge_rate = self.charge_rate.unwrap_or(defaults.charge_rate);
+        ship_stats.bullet_speed = self.bullet_speed.unwrap_or(defaults.bullet_speed);
+        ship_stats.shield_regeneration = self.shield_regeneration.unwrap_or(defaults.shield_regeneration);
+    }
+}
+
+// One level file in assets/levels
+#[derive(Asset, TypePath, Clone, Debug, Deserialize)]
+pub struct LevelDefinition {
+    pub name: String,
+    #[serde(default)]
+    pub player_spawn: (f32, f32), // Only used when the game starts on this level
+    #[serde(default)]
+    pub asteroids: Vec<AsteroidPlacement>,
+    #[serde(default)]
+    pub random_asteroids: Vec<AsteroidGroup>,
+    #[serde(default)]
+    pub speed: (f32, f32), // Range of starting speeds of the random asteroids
+    #[serde(default)]
+    pub ship_stats: ShipStatsOverride,
+    #[serde(default)]
+    pub playfield: Option<(f32, f32)>, // Width and height, the default playfield unless given
+}
+
+impl LevelDefinition {
+    pub fn playfield(&self) -> Playfield {
+        self.playfield.map(|(width, height)| Playfield::new(width, height)).unwrap_or_default()
+    }
+}
+
+// The order of the level files, from assets/levels/campaign.levels.ron
+#[derive(Asset, TypePath, Clone, Debug, Deserialize)]
+pub struct LevelList {
+    pub levels: Vec<String>,
+}
+
+// Copies of the level files, built into the binary for when the assets folder can't be read
+const BUILT_IN_LEVELS: [&str; 5] = [
+    include_str!("../assets/levels/01_first_contact.level.ron"),
+    include_str!("../assets/levels/02_crossfire.level.ron"),
+    include_str!("../assets/levels/03_heavy_rain.level.ron"),
+    include_str!("../assets/levels/04_swarm.level.ron"),
+    include_str!("../assets/levels/05_iron_belt.level.ron"),
+];
+
+// All levels in order. Read from the files at startup, and replaced by LevelAssetsPlugin when the files change.
+#[derive(Resource)]
+pub struct Levels {
+    pub levels: Vec<LevelDefinition>,
+}
+
+impl Default for Levels {
+    fn default() -> Self {
+        Self {
+            levels: BUILT_IN_LEVELS.iter().map(|ron_string| ron::from_str(ron_string).unwrap()).collect(),
+        }
+    }
+}
+
+impl Levels {
+    // Reads the levels from the assets folder, falling back to the levels built into the binary
+    pub fn load() -> Self {
+        match Self::read_files() {
+            Ok(levels) => levels,
+            Err(error) => {
+                // Missing files are expected where there is no file system, like on the web
+                if !error.is_empty() {
+                    error!("Could not load levels: {}", error);
+                }
+                Self::default()
+            }
+        }
+    }
+
+    fn read_files() -> Result<Self, String> {
+        let list_string = std::fs::read_to_string(format!("assets/{}/{}", LEVELS_FOLDER, LEVEL_LIST_FILE)).map_err(|_| String::new())?;
+        let list: LevelList = ron::from_str(&list_string).map_err(|error| format!("{}: {}", LEVEL_LIST_FILE, error))?;
+        let mut levels = Vec::new();
+        for file_name in list.levels.iter() {
+            let level_string = std::fs::read_to_string(format!("assets/{}/{}", LEVELS_FOLDER, file_name)).map_err(|error| format!("{}: {}", file_name, error))?;
+            levels.push(ron::from_str(&level_string).map_err(|error| format!("{}: {}", file_name, error))?);
+        }
+        if levels.is_empty() {
+            return Err(format!("{} has no levels", LEVEL_LIST_FILE));
+        }
+        Ok(Self { levels })
+    }
+
+    // Index into the level list for a wave, counting waves from 1. Waves past the last level use the last one.
+    pub fn index(&self, wave: u32) -> usize {
+        ((wave.max(1) - 1) as usize).min(self.levels.len() - 1)
+    }
+
+    // Level for a wave. Waves past the last level repeat it with faster asteroids.
+    pub fn get(&self, wave: u32) -> LevelDefinition {
+        let mut level = self.levels[self.index(wave)].clone();
+        let waves_past_end = (wave.max(1) as usize).saturating_sub(self.levels.len());
+        if waves_past_end > 0 {
+            let speed_factor = 1.0 + WAVE_SPEED_INCREASE * waves_past_end as f32;
+            level.speed = (level.speed.0 * speed_factor, level.speed.1 * speed_factor);
+            for asteroid in level.asteroids.iter_mut() {
+                asteroid.velocity = (asteroid.velocity.0 * speed_factor, asteroid.velocity.1 * speed_factor);
+            }
+        }
+        level
+    }
+}
+
+// Current wave, counting from 1. The wave is in progress from when its asteroids are spawned until the last one is destroyed.
+#[derive(Resource)]
+pub struct Wave {
+    pub number: u32,
+    pub in_progress: bool,
+    pub next_wave_at: u64, // Tick
+}
+impl Default for Wave {
+    fn default() -> Self {
+        Self {
+            number: 1,
+            in_progress: false,
+            next_wave_at: 0,
+        }
+    }
+}
+
+#[derive(Default)]
+pub struct LevelLoader;
+
+impl AssetLoader for LevelLoader {
+    type Asset = LevelDefinition;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes::<LevelDefinition>(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["level.ron"]
+    }
+}
+
+#[derive(Default)]
+pub struct LevelListLoader;
+
+impl AssetLoader for LevelListLoader {
+    type Asset = LevelList;
+    type Settings = ();
+    type Error = Box<dyn std::error::Error + Send + Sync>;
+
+    fn load<'a>(
+        &'a self,
+        reader: &'a mut Reader,
+        _settings: &'a (),
+        _load_context: &'a mut LoadContext,
+    ) -> BoxedFuture<'a, Result<Self::Asset, Self::Error>> {
+        Box::pin(async move {
+            let mut bytes = Vec::new();
+            reader.read_to_end(&mut bytes).await?;
+            Ok(ron::de::from_bytes::<LevelList>(&bytes)?)
+        })
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["levels.ron"]
+    }
+}
\ No newline at end of file
@@ -1,6 +1,26 @@
 use bevy::prelude::*;
+use crate::consts::*;
+
+// Number of simulation ticks since the game started
+#[derive(Resource, Default)]
+pub struct SimTick(pub u64);
+
+pub fn ticks_from_seconds(seconds: f32) -> u64 {
+    (seconds * TICKS_PER_SECOND as f32).round() as u64
+}
 
 #[derive(Component)]
-pub struct Lifetime(pub instant::Duration);
+pub struct Lifetime(pub u64); // Ticks
+impl Lifetime {
+    pub fn from_seconds(seconds: f32) -> Self {
+        Self(ticks_from_seconds(seconds))
+    }
+}
+
 #[derive(Component)]
-pub struct SpawnTime(pub instant::Instant);
\ No newline at end of file
+pub struct SpawnTime(pub u64); // Tick
+impl SpawnTime {
+    pub fn age_seconds(&self, sim_tick: &SimTick) -> f32 {
+        sim_tick.0.saturating_sub(self.0) as f32 / TICKS_PER_SECOND as f32
+    }
+}
\ No newline at end of file
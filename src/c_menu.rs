@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+
+// What a menu button does when pressed
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MenuAction {
+    Start,
+    Options,
+    Quit,
+    Resume,
+    Restart,
+    QuitToMenu,
+}
+impl MenuAction {
+    pub fn label(&self) -> &'static str {
+        match self {
+            MenuAction::Start => "Start",
+            MenuAction::Options => "Options",
+            MenuAction::Quit => "Quit",
+            MenuAction::Resume => "Resume",
+            MenuAction::Restart => "Restart",
+            MenuAction::QuitToMenu => "Quit to menu",
+        }
+    }
+}
+
+#[derive(Component)]
+pub struct MenuButton {
+    pub action: MenuAction,
+    pub index: usize, // From the top
+}
+
+// The button that Enter or the A button presses
+#[derive(Resource, Default)]
+pub struct MenuSelection(pub usize);
\ No newline at end of file
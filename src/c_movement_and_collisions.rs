@@ -1,4 +1,9 @@
 use bevy::prelude::*;
+use rand::Rng;
+use serde::Deserialize;
+use crate::consts::*;
+use crate::convex::{convex_hull, cut_piece, rotate};
+use crate::helpers::rf32;
 
 #[derive(Component)]
 pub struct Radius(pub f32);
@@ -8,6 +13,39 @@ impl Default for Radius {
     }
 }
 
+// Irregular outline of a body, counterclockwise around its position and turned with its Angle.
+// Stays inside Radius, which is still what the broad phase uses.
+#[derive(Clone, Component, Debug)]
+pub struct ConvexHull(pub Vec<Vec2>);
+impl ConvexHull {
+    // A lumpy outline for an asteroid, with corners at uneven angles and distances
+    pub fn random(rng: &mut impl Rng, radius: f32) -> Self {
+        let step = 2.0 * std::f32::consts::PI / ASTEROID_HULL_CORNERS as f32;
+        let corners: Vec<Vec2> = (0..ASTEROID_HULL_CORNERS)
+            .map(|i| {
+                let angle = (i as f32 + rf32(rng, -0.3, 0.3)) * step;
+                Vec2::from_angle(angle) * radius * rf32(rng, ASTEROID_HULL_MIN_RADIUS, 1.0)
+            })
+            .collect();
+        Self(convex_hull(&corners))
+    }
+
+    // The outline of a fragment breaking off at offset from the center, the third of this outline around it.
+    // Pulled into the range of sizes of a random outline, and turned back by the angle of the fragment like any outline.
+    pub fn fragment(&self, angle: f32, offset: Vec2, fragment_angle: f32, radius: f32) -> Self {
+        let piece: Vec<Vec2> = cut_piece(&rotate(&self.0, angle), offset, std::f32::consts::PI / 3.0)
+            .iter()
+            .map(|corner| *corner - offset)
+            .collect();
+        let scale = radius / piece.iter().map(|corner| corner.length()).fold(0.0, f32::max);
+        let corners: Vec<Vec2> = piece
+            .iter()
+            .map(|corner| corner.normalize_or_zero() * (corner.length() * scale).max(radius * ASTEROID_HULL_MIN_RADIUS))
+            .collect();
+        Self(convex_hull(&rotate(&corners, -fragment_angle)))
+    }
+}
+
 #[derive(Clone, Copy, Component)]
 pub struct Mass(pub f32);
 impl Default for Mass {
@@ -16,6 +54,24 @@ impl Default for Mass {
     }
 }
 
+// Fraction of the approach speed kept after a bounce
+#[derive(Clone, Copy, Component)]
+pub struct Restitution(pub f32);
+impl Default for Restitution {
+    fn default() -> Self {
+        Self(0.95)
+    }
+}
+
+// Coulomb friction coefficient between sliding surfaces
+#[derive(Clone, Copy, Component)]
+pub struct Friction(pub f32);
+impl Default for Friction {
+    fn default() -> Self {
+        Self(0.0)
+    }
+}
+
 #[derive(Component)]
 pub struct Angle(pub f32);
 impl Default for Angle {
@@ -24,7 +80,11 @@ impl Default for Angle {
     }
 }
 
-#[derive(Clone, Copy, Component)]
+// Spin in radians per second, turning the Angle. Bodies without it never pick up spin from collisions.
+#[derive(Clone, Copy, Component, Default)]
+pub struct AngularVelocity(pub f32);
+
+#[derive(Clone, Copy, Component, Debug)]
 pub struct Velocity {
     pub x: f32,
     pub y: f32,
@@ -35,7 +95,7 @@ impl Default for Velocity {
     }
 }
 
-#[derive(Component)]
+#[derive(Clone, Copy, Component, Debug, Deserialize, PartialEq, Eq, Hash)]
 pub enum CollisionType {
     Ship,
     Asteroid,
@@ -43,17 +103,33 @@ pub enum CollisionType {
     Bullet,
 }
 impl CollisionType {
-    pub fn is_ship(&self) -> bool {
-        matches!(*self, CollisionType::Ship)
-    }
-    pub fn is_asteroid(&self) -> bool {
-        matches!(*self, CollisionType::Asteroid)
-    }
     pub fn is_shield(&self) -> bool {
         matches!(*self, CollisionType::Shield)
     }
-    pub fn is_bullet(&self) -> bool {
-        matches!(*self, CollisionType::Bullet)
-    }
 }
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    proptest! {
+        #[test]
+        fn fragment_outline_fits_around_its_center(seed in any::<u64>(), angle in 0.0f32..6.3, direction in 0.0f32..6.3, fragment_angle in 0.0f32..6.3) {
+            // A big asteroid breaking into medium ones, the way spawn_asteroid_fragments places them
+            let hull = ConvexHull::random(&mut ChaCha8Rng::seed_from_u64(seed), 88.0);
+            let offset = Vec2::from_angle(direction) * 88.0 * 0.54;
+            let fragment = hull.fragment(angle, offset, fragment_angle, 39.0);
+            prop_assert!(fragment.0.len() >= 3);
+            for (i, corner) in fragment.0.iter().enumerate() {
+                prop_assert!(corner.length() <= 39.0 + 1e-3);
+                // The center is on the inside of every counterclockwise edge
+                let edge = fragment.0[(i + 1) % fragment.0.len()] - *corner;
+                prop_assert!(edge.perp_dot(-*corner) > 0.0);
+            }
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,86 @@
+use bevy::prelude::*;
+use crate::consts::*;
+use crate::c_controls::{Action, Controls};
+use crate::helpers::arg_value;
+
+// Which of the local players a ship belongs to, from 0. Stays the same when the ship respawns.
+#[derive(Clone, Copy, Component, Debug, Default, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct PlayerId(pub usize);
+
+// The player who fired a bullet, to credit the kill when it destroys a ship
+#[derive(Clone, Copy, Component, Debug)]
+pub struct FiredBy(pub PlayerId);
+
+// Number of ships playing on this machine. Set with "--players N", from 1 to 4.
+#[derive(Resource)]
+pub struct LocalPlayers(pub usize);
+impl Default for LocalPlayers {
+    fn default() -> Self {
+        let players = arg_value("--players")
+        .and_then(|players| players.parse::<usize>().ok())
+        .unwrap_or(1)
+        .clamp(1, MAX_PLAYERS);
+        Self(players)
+    }
+}
+
+// What sets the players apart
+pub struct PlayerProfile {
+    pub controls: Controls, // The default bindings, keys and the gamepad of the same number
+    pub tint: Color, // Of the ship sprite
+    pub shield_color: i32, // Blue, Green, Orange, Purple, from the color gradients of the shield shader
+}
+
+impl PlayerId {
+    pub fn profile(&self) -> PlayerProfile {
+        match self.0 {
+            1 => PlayerProfile {
+                controls: Controls::new(1, [
+                    (Action::Accelerate, KeyCode::W),
+                    (Action::TurnLeft, KeyCode::A),
+                    (Action::TurnRight, KeyCode::D),
+                    (Action::Fire, KeyCode::ShiftLeft),
+                    (Action::Shield, KeyCode::ControlLeft),
+                ]),
+                tint: Color::rgb(0.7, 1.0, 0.7),
+                shield_color: 1,
+            },
+            2 => PlayerProfile {
+                controls: Controls::new(2, [
+                    (Action::Accelerate, KeyCode::I),
+                    (Action::TurnLeft, KeyCode::J),
+                    (Action::TurnRight, KeyCode::L),
+                    (Action::Fire, KeyCode::O),
+                    (Action::Shield, KeyCode::U),
+                ]),
+                tint: Color::rgb(1.0, 0.8, 0.6),
+                shield_color: 2,
+            },
+            3 => PlayerProfile {
+                controls: Controls::new(3, [
+                    (Action::Accelerate, KeyCode::Numpad8),
+                    (Action::TurnLeft, KeyCode::Numpad4),
+                    (Action::TurnRight, KeyCode::Numpad6),
+                    (Action::Fire, KeyCode::Numpad0),
+                    (Action::Shield, KeyCode::NumpadDecimal),
+                ]),
+                tint: Color::rgb(0.9, 0.7, 1.0),
+                shield_color: 3,
+            },
+            _ => PlayerProfile {
+                controls: Controls::default(),
+                tint: Color::WHITE,
+                shield_color: 0,
+            },
+        }
+    }
+
+    // The players start spread on a circle around the spawn point of the level. A single player starts right on it.
+    pub fn spawn_point(&self, level_spawn: Vec2, players: usize) -> Vec2 {
+        if players <= 1 {
+            return level_spawn;
+        }
+        let angle = PI + self.0 as f32 * 2.0 * PI / players as f32;
+        level_spawn + Vec2::from_angle(angle) * PLAYER_SPAWN_SPREAD
+    }
+}
\ No newline at end of file
@@ -0,0 +1,38 @@
+use bevy::prelude::*;
+use crate::consts::*;
+use crate::torus::Torus;
+
+// Size of the world, which loops around at the edges. The world origin is in the middle.
+// Independent of the window, the camera scales the playfield to fit and letterboxes it.
+#[derive(Resource, Clone, Copy, Debug, PartialEq)]
+pub struct Playfield {
+    pub width: f32,
+    pub height: f32,
+}
+
+impl Default for Playfield {
+    fn default() -> Self {
+        Self {
+            width: PLAYFIELD_WIDTH,
+            height: PLAYFIELD_HEIGHT,
+        }
+    }
+}
+
+impl Playfield {
+    pub fn new(width: f32, height: f32) -> Self {
+        Self { width, height }
+    }
+
+    pub fn size(&self) -> Vec2 {
+        Vec2::new(self.width, self.height)
+    }
+
+    pub fn half_size(&self) -> Vec2 {
+        self.size() / 2.0
+    }
+
+    pub fn torus(&self) -> Torus {
+        Torus::new(self.size())
+    }
+}
\ No newline at end of file
@@ -0,0 +1,58 @@
+use bevy::prelude::*;
+use serde::{Deserialize, Serialize};
+use crate::consts::*;
+use crate::c_controls::ControlFrame;
+
+// Everything needed to play a game back: the seed, the number of players, and the controls of every player on every tick.
+// The simulation is deterministic, so feeding the same controls on the same ticks gives the same game.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub players: usize,
+    pub ticks: Vec<Vec<ControlFrame>>, // Entry 0 is tick 1. One frame per player, by PlayerId.
+}
+
+impl Replay {
+    pub fn new(seed: u64, players: usize) -> Self {
+        Self {
+            version: REPLAY_VERSION,
+            seed,
+            players,
+            ticks: Vec::new(),
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let ron_string = std::fs::read_to_string(path).map_err(|error| error.to_string())?;
+        let replay: Replay = ron::from_str(&ron_string).map_err(|error| error.to_string())?;
+        if replay.version != REPLAY_VERSION {
+            return Err(format!("replay version {} is not supported, expected {}", replay.version, REPLAY_VERSION));
+        }
+        Ok(replay)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        let ron_string = ron::ser::to_string(self).map_err(|error| error.to_string())?;
+        std::fs::write(path, ron_string).map_err(|error| error.to_string())
+    }
+
+    pub fn frames(&self, tick: u64) -> Option<&Vec<ControlFrame>> {
+        self.ticks.get((tick as usize).checked_sub(1)?)
+    }
+}
+
+// Present while recording with --record
+#[derive(Resource)]
+pub struct ReplayRecording {
+    pub path: String,
+    pub replay: Replay,
+    pub saved_ticks: usize, // Number of ticks in the file the last time it was saved
+}
+
+// Present while playing back with --replay. Replaces the keyboard as the source of the controls.
+#[derive(Resource)]
+pub struct ReplayPlayback {
+    pub replay: Replay,
+    pub finished: bool,
+}
\ No newline at end of file
@@ -0,0 +1,34 @@
+use bevy::prelude::*;
+use crate::consts::*;
+use crate::helpers::arg_value;
+
+#[derive(Resource, Default)]
+pub struct Score(pub u32);
+
+// Ships destroyed by each player's bullets, not counting their own ship
+#[derive(Resource, Default)]
+pub struct Kills(pub [u32; MAX_PLAYERS]);
+
+// Ships left, including the ones in play, shared by all players.
+// Each player brings the starting number into the game, which can be set with "--lives N".
+#[derive(Resource)]
+pub struct Lives {
+    pub remaining: u32,
+    pub starting: u32, // For each player
+}
+impl Default for Lives {
+    fn default() -> Self {
+        let starting = arg_value("--lives")
+        .and_then(|lives| lives.parse::<u32>().ok())
+        .unwrap_or(STARTING_LIVES)
+        .max(1);
+        Self {
+            remaining: starting,
+            starting,
+        }
+    }
+}
+
+// Tick when each player's next ship may be spawned, after the previous one was destroyed
+#[derive(Resource, Default)]
+pub struct RespawnAt(pub [u64; MAX_PLAYERS]);
\ No newline at end of file
@@ -0,0 +1,133 @@
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+use serde::{Deserialize, Serialize};
+use crate::consts::*;
+use crate::c_controls::{Action, Binding, Controls};
+use crate::c_players::PlayerId;
+
+// Everything the player can change in the options, kept between runs.
+// Fields missing from the stored file keep their defaults, so older files still load.
+#[derive(Clone, Debug, Deserialize, PartialEq, Resource, Serialize)]
+#[serde(default)]
+pub struct Settings {
+    pub controls: Vec<Controls>, // The binding profile of each player, by PlayerId
+    pub volume: f32, // From 0 to 1
+    pub screen_shake: f32, // Scales the shake of the camera, 0 turns it off
+    pub window_mode: WindowMode,
+}
+
+impl Default for Settings {
+    fn default() -> Self {
+        Self {
+            controls: (0..MAX_PLAYERS).map(|player| PlayerId(player).profile().controls).collect(),
+            volume: 0.8,
+            screen_shake: 1.0,
+            window_mode: WindowMode::Windowed,
+        }
+    }
+}
+
+impl Settings {
+    pub fn from_ron(ron_string: &str) -> Result<Self, String> {
+        let mut settings: Self = ron::from_str(ron_string).map_err(|error| error.to_string())?;
+        // Every player has a profile, also when the file was written with fewer
+        for player in settings.controls.len()..MAX_PLAYERS {
+            settings.controls.push(PlayerId(player).profile().controls);
+        }
+        settings.controls.truncate(MAX_PLAYERS);
+        settings.volume = settings.volume.clamp(0.0, 1.0);
+        settings.screen_shake = settings.screen_shake.clamp(0.0, 1.0);
+        Ok(settings)
+    }
+
+    pub fn to_ron(&self) -> Result<String, String> {
+        ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::default()).map_err(|error| error.to_string())
+    }
+
+    // Reads the stored settings, with the defaults on the first run or if the stored ones are broken
+    pub fn load() -> Self {
+        match read_stored() {
+            Ok(Some(ron_string)) => match Self::from_ron(&ron_string) {
+                Ok(settings) => settings,
+                Err(error) => {
+                    error!("Could not parse {}: {}", SETTINGS_FILE, error);
+                    Self::default()
+                }
+            },
+            Ok(None) => Self::default(),
+            Err(error) => {
+                error!("Could not read {}: {}", SETTINGS_FILE, error);
+                Self::default()
+            }
+        }
+    }
+
+    pub fn save(&self) -> Result<(), String> {
+        write_stored(&self.to_ron()?)
+    }
+
+    // Who else already uses a binding for something else, if anyone.
+    // The keyboard is shared by all players, a gamepad only by the players set to the same gamepad.
+    pub fn conflict(&self, player_id: PlayerId, action: Action, binding: Binding) -> Option<(PlayerId, Action)> {
+        let gamepad = self.controls.get(player_id.0)?.gamepad;
+        self.controls.iter().enumerate()
+        .filter(|(_, controls)| binding.is_key() || controls.gamepad == gamepad)
+        .flat_map(|(other, controls)| controls.bindings.iter().map(move |(other_action, other_binding)| (PlayerId(other), *other_action, *other_binding)))
+        .find(|(other, other_action, other_binding)| *other_binding == binding && (*other != player_id || *other_action != action))
+        .map(|(other, other_action, _)| (other, other_action))
+    }
+}
+
+// The platform config folder: the roaming AppData on Windows, Application Support on macOS, and XDG_CONFIG_HOME or ~/.config elsewhere
+#[cfg(not(target_arch = "wasm32"))]
+pub fn settings_path() -> Option<std::path::PathBuf> {
+    use std::{env, path::PathBuf};
+    let config_folder = if cfg!(target_os = "windows") {
+        env::var_os("APPDATA").map(PathBuf::from)
+    }
+    else if cfg!(target_os = "macos") {
+        env::var_os("HOME").map(|home| PathBuf::from(home).join("Library").join("Application Support"))
+    }
+    else {
+        env::var_os("XDG_CONFIG_HOME").map(PathBuf::from).filter(|folder| folder.is_absolute())
+        .or_else(|| env::var_os("HOME").map(|home| PathBuf::from(home).join(".config")))
+    };
+    Some(config_folder?.join(SETTINGS_FOLDER).join(SETTINGS_FILE))
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn read_stored() -> Result<Option<String>, String> {
+    let path = settings_path().ok_or("no config folder")?;
+    match std::fs::read_to_string(path) {
+        Ok(ron_string) => Ok(Some(ron_string)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(error.to_string()),
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+fn write_stored(ron_string: &str) -> Result<(), String> {
+    let path = settings_path().ok_or("no config folder")?;
+    if let Some(folder) = path.parent() {
+        std::fs::create_dir_all(folder).map_err(|error| error.to_string())?;
+    }
+    std::fs::write(path, ron_string).map_err(|error| error.to_string())
+}
+
+// In the browser the settings live in localStorage, under the name of the file
+#[cfg(target_arch = "wasm32")]
+fn local_storage() -> Result<web_sys::Storage, String> {
+    web_sys::window()
+    .and_then(|window| window.local_storage().ok().flatten())
+    .ok_or_else(|| "localStorage is not available".to_string())
+}
+
+#[cfg(target_arch = "wasm32")]
+fn read_stored() -> Result<Option<String>, String> {
+    local_storage()?.get_item(SETTINGS_FILE).map_err(|error| format!("{:?}", error))
+}
+
+#[cfg(target_arch = "wasm32")]
+fn write_stored(ron_string: &str) -> Result<(), String> {
+    local_storage()?.set_item(SETTINGS_FILE, ron_string).map_err(|error| format!("{:?}", error))
+}
\ No newline at end of file
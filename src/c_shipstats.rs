@@ -1,9 +1,8 @@
 use bevy::prelude::*;
-use crate::c_controls::Controls;
+use crate::consts::*;
 
 #[derive(Component)]
 pub struct ShipStats {
-    pub controls: Controls,
     pub acceleration: f32,
     pub turn_rate: f32,
     pub charge_rate: f32,
@@ -13,7 +12,6 @@ pub struct ShipStats {
 impl Default for ShipStats {
     fn default() -> Self {
         Self {
-            controls: Controls::default(),
             acceleration: 300.0,
             turn_rate: 4.0,
             charge_rate: 3.0,
@@ -23,10 +21,14 @@ impl Default for ShipStats {
     }
 }
 
+// The ship can't be hurt until this tick. Shown with the shield, which doesn't drain energy meanwhile.
+#[derive(Component)]
+pub struct Invulnerable(pub u64);
+
 #[derive(Component)]
 pub struct Energy(pub f32);
 impl Default for Energy {
     fn default() -> Self {
-        Self(100.0)
+        Self(MAX_ENERGY)
     }
 }
\ No newline at end of file
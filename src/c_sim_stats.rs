@@ -0,0 +1,28 @@
+use bevy::prelude::*;
+use crate::consts::*;
+
+// Statistics of one game, collected while the simulation runs
+#[derive(Resource, Default, Clone, Debug)]
+pub struct SimStats {
+    pub ticks: u64,
+    pub lives_lost: u32,
+    pub game_over: bool, // Set when the last life was lost before the time ran out
+    pub big_asteroids_destroyed: u32,
+    pub medium_asteroids_destroyed: u32,
+    pub small_asteroids_destroyed: u32,
+    pub shots_fired: u32,
+    pub shield_ticks: u64,
+    pub waves_cleared: u32,
+    pub strongest_collision: f32, // Largest impulse of a collision, as if it was perfectly elastic
+}
+
+impl SimStats {
+    pub fn seconds_survived(&self) -> f32 {
+        self.ticks as f32 / TICKS_PER_SECOND as f32
+    }
+
+    pub fn shield_uptime(&self) -> f32 {
+        if self.ticks == 0 { return 0.0; }
+        self.shield_ticks as f32 / self.ticks as f32
+    }
+}
\ No newline at end of file
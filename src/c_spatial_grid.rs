@@ -0,0 +1,183 @@
+use bevy::prelude::*;
+use crate::consts::*;
+use crate::c_playfield::Playfield;
+
+// Broad-phase grid covering the whole torus. Bodies are inserted into every cell their bounding box touches,
+// with cell indices wrapping around the playfield edges, so bodies overlapping across a seam still share a cell.
+#[derive(Resource)]
+pub struct SpatialGrid {
+    pub playfield: Playfield,
+    pub columns: usize,
+    pub rows: usize,
+    pub cell_width: f32,
+    pub cell_height: f32,
+    pub bodies: Vec<GridBody>,
+    cells: Vec<Vec<usize>>,
+}
+
+#[derive(Clone, Copy)]
+pub struct GridBody {
+    pub entity: Entity,
+    pub position: Vec2,
+    pub radius: f32,
+}
+
+impl Default for SpatialGrid {
+    fn default() -> Self {
+        Self::new(Playfield::default(), COLLISION_GRID_CELL_SIZE)
+    }
+}
+
+impl SpatialGrid {
+    // Cells are stretched slightly so that a whole number of them covers the torus exactly
+    pub fn new(playfield: Playfield, cell_size: f32) -> Self {
+        let columns = ((playfield.width / cell_size).floor() as usize).max(1);
+        let rows = ((playfield.height / cell_size).floor() as usize).max(1);
+        Self {
+            playfield,
+            columns,
+            rows,
+            cell_width: playfield.width / columns as f32,
+            cell_height: playfield.height / rows as f32,
+            bodies: Vec::new(),
+            cells: vec![Vec::new(); columns * rows],
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.bodies.clear();
+        for cell in self.cells.iter_mut() {
+            cell.clear();
+        }
+    }
+
+    pub fn insert(&mut self, entity: Entity, position: Vec2, radius: f32) {
+        let index = self.bodies.len();
+        self.bodies.push(GridBody { entity, position, radius });
+
+        // The world origin is in the middle of the playfield, so cell 0 starts at the bottom left corner
+        let (column_min, column_span) = Self::cell_span(position.x + self.playfield.width / 2.0, radius, self.cell_width, self.columns);
+        let (row_min, row_span) = Self::cell_span(position.y + self.playfield.height / 2.0, radius, self.cell_height, self.rows);
+        for row_offset in 0..row_span {
+            let row = (row_min + row_offset as i64).rem_euclid(self.rows as i64) as usize;
+            for column_offset in 0..column_span {
+                let column = (column_min + column_offset as i64).rem_euclid(self.columns as i64) as usize;
+                self.cells[row * self.columns + column].push(index);
+            }
+        }
+    }
+
+    // First cell touched along one axis and the number of cells touched, never more than the whole axis
+    fn cell_span(coordinate: f32, radius: f32, cell_size: f32, cell_count: usize) -> (i64, usize) {
+        let radius = radius + 0.01; // Small margin so rounding at cell borders can never drop a contact
+        let min = ((coordinate - radius) / cell_size).floor() as i64;
+        let max = ((coordinate + radius) / cell_size).floor() as i64;
+        let span = ((max - min + 1) as usize).min(cell_count);
+        (min, span)
+    }
+
+    // Pairs of body indices sharing at least one cell, sorted and without duplicates.
+    // The order matches the one iter_combinations gives over the same list of bodies.
+    pub fn candidate_pairs(&self) -> Vec<(usize, usize)> {
+        let mut pairs = Vec::<(usize, usize)>::new();
+        for cell in self.cells.iter() {
+            for (i, index_1) in cell.iter().enumerate() {
+                for index_2 in cell.iter().skip(i + 1) {
+                    // Indices are pushed in insertion order, so each cell is already sorted
+                    pairs.push((*index_1, *index_2));
+                }
+            }
+        }
+        pairs.sort_unstable();
+        pairs.dedup();
+        pairs
+    }
+
+    // Pairs of entities that are actually touching, taking edge looping into account
+    pub fn contact_pairs(&self) -> Vec<(Entity, Entity)> {
+        self.candidate_pairs()
+        .into_iter()
+        .filter(|(index_1, index_2)| bodies_touch(&self.playfield, &self.bodies[*index_1], &self.bodies[*index_2]))
+        .map(|(index_1, index_2)| (self.bodies[index_1].entity, self.bodies[index_2].entity))
+        .collect()
+    }
+}
+
+pub fn bodies_touch(playfield: &Playfield, body_1: &GridBody, body_2: &GridBody) -> bool {
+    playfield.torus().distance(body_1.position, body_2.position) < body_1.radius + body_2.radius
+}
+
+// Reference O(n²) implementation, used to verify and benchmark the grid
+pub fn brute_force_contact_pairs(playfield: &Playfield, bodies: &[GridBody]) -> Vec<(Entity, Entity)> {
+    let mut pairs = Vec::<(Entity, Entity)>::new();
+    for (i, body_1) in bodies.iter().enumerate() {
+        for body_2 in bodies.iter().skip(i + 1) {
+            if bodies_touch(playfield, body_1, body_2) {
+                pairs.push((body_1.entity, body_2.entity));
+            }
+        }
+    }
+    pairs
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    // Where a body sits along one axis: anywhere, as a fraction of the axis, or close to the seam, as an offset from it
+    #[derive(Clone, Copy, Debug)]
+    enum Along {
+        Anywhere(f32),
+        Seam(f32),
+    }
+
+    fn along() -> impl Strategy<Value = Along> {
+        prop_oneof![
+            (0.0f32..1.0).prop_map(Along::Anywhere),
+            (-100.0f32..100.0).prop_map(Along::Seam),
+        ]
+    }
+
+    // Half the bodies are close to the seam on each axis, so about a quarter of them are around the corner cell
+    fn bodies() -> impl Strategy<Value = Vec<(Along, Along, f32)>> {
+        prop::collection::vec((along(), along(), 1.0f32..120.0), 0..150)
+    }
+
+    fn coordinate(along: Along, size: f32) -> f32 {
+        match along {
+            Along::Anywhere(fraction) => (fraction - 0.5) * size,
+            Along::Seam(offset) => size / 2.0 + offset,
+        }
+    }
+
+    #[test]
+    fn bodies_touch_across_the_corner() {
+        // One body in each corner cell, touching the other three only across the seams
+        let playfield = Playfield::default();
+        let mut grid = SpatialGrid::default();
+        let corner = Vec2::new(playfield.width / 2.0 - 5.0, playfield.height / 2.0 - 5.0);
+        for (index, sign) in [Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(-1.0, 1.0), Vec2::new(1.0, 1.0)].iter().enumerate() {
+            grid.insert(Entity::from_raw(index as u32), corner * *sign, 10.0);
+        }
+
+        assert_eq!(grid.contact_pairs().len(), 6);
+        assert_eq!(grid.contact_pairs(), brute_force_contact_pairs(&playfield, &grid.bodies));
+    }
+
+    proptest! {
+        #[test]
+        fn grid_finds_the_same_contacts_as_brute_force(
+            width in 300.0f32..2000.0, height in 300.0f32..2000.0, cell_size in 20.0f32..300.0, bodies in bodies(),
+        ) {
+            let playfield = Playfield::new(width, height);
+            let mut grid = SpatialGrid::new(playfield, cell_size);
+            for (index, (along_x, along_y, radius)) in bodies.into_iter().enumerate() {
+                let position = playfield.torus().wrap(Vec2::new(coordinate(along_x, width), coordinate(along_y, height)));
+                grid.insert(Entity::from_raw(index as u32), position, radius);
+            }
+
+            prop_assert_eq!(grid.contact_pairs(), brute_force_contact_pairs(&playfield, &grid.bodies));
+        }
+    }
+}
\ No newline at end of file
@@ -0,0 +1,110 @@
+use bevy::prelude::*;
+use serde::Deserialize;
+use std::collections::HashMap;
+use crate::consts::*;
+
+// Which material a sprite is drawn with
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+pub enum SpriteMaterialKind {
+    #[default]
+    Basic, // Draws the image
+    Shield, // The shield shader, with the image as its color gradients
+}
+
+// One image a sprite can be drawn with
+#[derive(Clone, Debug, Deserialize)]
+pub struct SpriteVariant {
+    pub image: String,
+    #[serde(default)]
+    pub region: Option<(u32, u32, u32, u32)>, // x, y, width, height in pixels, for sprites packed into an atlas
+}
+
+impl SpriteVariant {
+    // The part of the image to draw, in texture coordinates
+    pub fn uv_rect(&self, image_size: Vec2) -> Rect {
+        match self.region {
+            Some((x, y, width, height)) if image_size.x > 0.0 && image_size.y > 0.0 => Rect::new(
+                x as f32 / image_size.x,
+                y as f32 / image_size.y,
+                (x + width) as f32 / image_size.x,
+                (y + height) as f32 / image_size.y,
+            ),
+            _ => Rect::new(0.0, 0.0, 1.0, 1.0),
+        }
+    }
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct SpriteDefinition {
+    pub size: f32, // Width and height of the quad drawn
+    pub z: f32,
+    #[serde(default)]
+    pub material: SpriteMaterialKind,
+    pub variants: Vec<SpriteVariant>,
+}
+
+// Maps the ids of the sprites to how they are drawn, so images can be changed or added without recompiling
+#[derive(Clone, Debug, Deserialize, Resource)]
+pub struct SpriteManifest {
+    pub background: String,
+    pub sprites: HashMap<String, SpriteDefinition>,
+}
+
+impl Default for SpriteManifest {
+    fn default() -> Self {
+        Self::from_ron(include_str!("../assets/sprites.ron")).unwrap()
+    }
+}
+
+impl SpriteManifest {
+    // Ids of every sprite the game draws
+    pub fn required_ids() -> Vec<String> {
+        let mut ids: Vec<String> = ["ship", "shield", "asteroid_big", "asteroid_medium", "asteroid_small"]
+            .iter()
+            .map(|id| id.to_string())
+            .collect();
+        ids.extend((0..BULLET_CHARGE_TIERS).map(bullet_sprite_id));
+        ids
+    }
+
+    pub fn from_ron(ron_string: &str) -> Result<Self, String> {
+        let manifest: Self = ron::from_str(ron_string).map_err(|error| error.to_string())?;
+        for id in Self::required_ids() {
+            match manifest.sprites.get(&id) {
+                None => return Err(format!("Missing sprite \"{}\"", id)),
+                Some(definition) if definition.variants.is_empty() => return Err(format!("Sprite \"{}\" has no variants", id)),
+                _ => {}
+            }
+        }
+        Ok(manifest)
+    }
+
+    // Reads the manifest from the assets folder, falling back to the one built into the binary if the file is missing or broken
+    pub fn load() -> Self {
+        match std::fs::read_to_string(format!("assets/{}", SPRITE_MANIFEST_FILE)) {
+            Ok(ron_string) => match Self::from_ron(&ron_string) {
+                Ok(manifest) => manifest,
+                Err(error) => {
+                    error!("Could not parse {}: {}", SPRITE_MANIFEST_FILE, error);
+                    Self::default()
+                }
+            },
+            Err(_) => Self::default(),
+        }
+    }
+
+    // Every image file used, each listed once
+    pub fn images(&self) -> Vec<String> {
+        let mut images: Vec<String> = self.sprites.values()
+            .flat_map(|definition| definition.variants.iter().map(|variant| variant.image.clone()))
+            .chain(std::iter::once(self.background.clone()))
+            .collect();
+        images.sort();
+        images.dedup();
+        images
+    }
+}
+
+pub fn bullet_sprite_id(tier: usize) -> String {
+    format!("bullet_{}", tier)
+}
\ No newline at end of file
@@ -1,19 +1,181 @@
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    render::mesh::VertexAttributeValues,
+    sprite::Mesh2dHandle,
+};
+use serde::Deserialize;
+use std::collections::HashMap;
+use crate::consts::*;
+use crate::c_players::PlayerId;
+use crate::c_sprite_manifest::{bullet_sprite_id, SpriteDefinition, SpriteManifest, SpriteMaterialKind};
+use crate::c_chargelevel::ChargeLevel;
+use crate::material_basic::MaterialBasic;
+use crate::material_shield::MaterialShield;
 
+// Every image in the sprite manifest, by file
 #[derive(Resource)]
 pub struct Textures{
-    pub ship: Handle<Image>,
-    pub shield: Handle<Image>,
-    pub bullet: Handle<Image>,
-    pub asteroid_1: Handle<Image>,
     pub background: Handle<Image>,
-    pub color_gradients: Handle<Image>,
+    pub images: HashMap<String, Handle<Image>>,
 }
 
+impl Textures {
+    // Every texture with its file, to wait for them to load
+    pub fn files(&self) -> Vec<(String, UntypedHandle)> {
+        let mut files: Vec<(String, UntypedHandle)> = self.images.iter()
+            .map(|(file, handle)| (file.clone(), handle.clone().untyped()))
+            .collect();
+        files.sort_by(|a, b| a.0.cmp(&b.0));
+        files
+    }
+}
+
+impl FromWorld for Textures {
+    fn from_world(world: &mut World) -> Self {
+        let manifest = world.resource::<SpriteManifest>();
+        let asset_server = world.resource::<AssetServer>();
+        Textures {
+            background: asset_server.load(manifest.background.clone()),
+            images: manifest.images().into_iter()
+                .map(|file| (file.clone(), asset_server.load(file)))
+                .collect(),
+        }
+    }
+}
+
+// One mesh and material for every sprite variant in the manifest, shared by all entities that look the same.
+// Built once the textures are loaded, so atlas regions can be turned into texture coordinates, and spawning never adds to the asset storage.
+#[derive(Resource)]
+pub struct RenderCache {
+    pub sprites: HashMap<String, Vec<CachedSprite>>, // By sprite id, one per variant
+    pub player_sprites: Vec<HashMap<String, Vec<CachedSprite>>>, // The ship and shield again for each player, in their colors
+}
+
+#[derive(Clone)]
+pub struct CachedSprite {
+    pub mesh: Mesh2dHandle,
+    pub material: SpriteMaterial,
+    pub quad_size: f32,
+    pub z: f32,
+}
+
+impl FromWorld for RenderCache {
+    fn from_world(world: &mut World) -> Self {
+        let manifest = world.resource::<SpriteManifest>().clone();
+        let images = world.resource::<Textures>().images.clone();
+        let sprites = manifest.sprites.iter()
+            .map(|(id, definition)| (id.clone(), cache_variants(world, definition, &images, Color::WHITE, 0)))
+            .collect();
+        let player_sprites = (0..MAX_PLAYERS).map(PlayerId)
+            .map(|player_id| {
+                let profile = player_id.profile();
+                PLAYER_SPRITES.iter()
+                    .map(|id| (id.to_string(), cache_variants(world, &manifest.sprites[*id], &images, profile.tint, profile.shield_color)))
+                    .collect()
+            })
+            .collect();
+        Self { sprites, player_sprites }
+    }
+}
+
+// Sprites drawn in the colors of the player they belong to
+const PLAYER_SPRITES: [&str; 2] = ["ship", "shield"];
+
+fn cache_variants(world: &mut World, definition: &SpriteDefinition, images: &HashMap<String, Handle<Image>>, tint: Color, shield_color: i32) -> Vec<CachedSprite> {
+    let mut variants = Vec::new();
+    for variant in definition.variants.iter() {
+        let image = images[&variant.image].clone_weak();
+        let image_size = world.resource::<Assets<Image>>().get(&image).map_or(Vec2::ZERO, |image| image.size_f32());
+        let material = match definition.material {
+            SpriteMaterialKind::Basic => SpriteMaterial::Basic(
+                world.resource_mut::<Assets<MaterialBasic>>().add(MaterialBasic { texture: Some(image), tint })
+            ),
+            SpriteMaterialKind::Shield => SpriteMaterial::Shield(
+                world.resource_mut::<Assets<MaterialShield>>().add(MaterialShield {
+                    color: shield_color,
+                    texture_gradient: Some(image),
+                    ..Default::default()
+                })
+            ),
+        };
+        variants.push(CachedSprite::new(world, material, definition.size, definition.z, variant.uv_rect(image_size)));
+    }
+    variants
+}
+
+impl RenderCache {
+    // The manifest id of the sprite an entity is drawn with
+    pub fn sprite_id(sprite_type: &SpriteType, asteroid_size: Option<&AsteroidSize>, charge_level: Option<&ChargeLevel>) -> String {
+        if sprite_type.is_shield() {
+            "shield".to_string()
+        }
+        else if let Some(asteroid_size) = asteroid_size {
+            match asteroid_size {
+                AsteroidSize::Big => "asteroid_big".to_string(),
+                AsteroidSize::Medium => "asteroid_medium".to_string(),
+                AsteroidSize::Small => "asteroid_small".to_string(),
+            }
+        }
+        else if let Some(charge_level) = charge_level {
+            let tier = (charge_level.0.floor().max(0.0) as usize).min(BULLET_CHARGE_TIERS - 1);
+            bullet_sprite_id(tier)
+        }
+        else {
+            "ship".to_string()
+        }
+    }
+
+    // The looks an entity can be drawn with. Every id the game uses is checked for when the manifest is read, so there is at least one.
+    pub fn variants(&self, sprite_type: &SpriteType, asteroid_size: Option<&AsteroidSize>, charge_level: Option<&ChargeLevel>, player_id: Option<PlayerId>) -> &[CachedSprite] {
+        let id = Self::sprite_id(sprite_type, asteroid_size, charge_level);
+        player_id
+        .and_then(|player_id| self.player_sprites.get(player_id.0)?.get(&id))
+        .unwrap_or(&self.sprites[&id])
+    }
+}
+
+impl CachedSprite {
+    fn new(world: &mut World, material: SpriteMaterial, quad_size: f32, z: f32, uv_rect: Rect) -> Self {
+        let mut mesh = Mesh::from(shape::Quad { size: Vec2::new(quad_size, quad_size), flip: false });
+        if let Some(VertexAttributeValues::Float32x2(uvs)) = mesh.attribute_mut(Mesh::ATTRIBUTE_UV_0) {
+            for uv in uvs.iter_mut() {
+                *uv = (uv_rect.min + Vec2::from(*uv) * uv_rect.size()).into();
+            }
+        }
+        let mesh = world.resource_mut::<Assets<Mesh>>().add(mesh);
+        Self { mesh: mesh.into(), material, quad_size, z }
+    }
+}
+
+// What an entity looks like, kept so copies can be added when it crosses an edge of the playfield
+#[derive(Component)]
+pub struct WrapSprite {
+    pub sprite: CachedSprite,
+    pub half_size: Vec2, // Of the bounds of the sprite, at any rotation
+}
+impl WrapSprite {
+    pub fn new(sprite: &CachedSprite) -> Self {
+        Self {
+            sprite: sprite.clone(),
+            half_size: Vec2::splat(sprite.quad_size * FRAC_1_SQRT_2), // The corners of the quad reach this far when it is rotated
+        }
+    }
+}
+
+#[derive(Clone)]
+pub enum SpriteMaterial {
+    Basic(Handle<MaterialBasic>),
+    Shield(Handle<MaterialShield>),
+}
+
+// A sprite drawn for an entity, offset by a whole playfield size when it is a copy showing across an edge
+#[derive(Component)]
+pub struct WrapCopy(pub Vec2);
+
 #[derive(Component)]
 pub enum SpriteType {
     Ship,
-    Asteroid1,
+    Asteroid,
     Shield,
     Bullet,
 }
@@ -25,8 +187,8 @@ impl SpriteType {
         matches!(*self, SpriteType::Shield)
     }
 /*
-    fn is_asteroid_1(&self) -> bool {
-        matches!(*self, SpriteType::Asteroid1)
+    fn is_asteroid(&self) -> bool {
+        matches!(*self, SpriteType::Asteroid)
     }
     fn is_bullet(&self) -> bool {
         matches!(*self, SpriteType::Bullet)
@@ -34,21 +196,25 @@ impl SpriteType {
 */
 }
 
-#[derive(Clone, Copy, Component)]
+#[derive(Clone, Copy, Component, Debug, Deserialize, PartialEq, Eq)]
 pub enum AsteroidSize {
     Small,
     Medium,
     Big
 }
 impl AsteroidSize {
-    pub fn is_small(&self) -> bool {
-        matches!(*self, AsteroidSize::Small)
-    }
     pub fn is_medium(&self) -> bool {
         matches!(*self, AsteroidSize::Medium)
     }
     pub fn is_big(&self) -> bool {
         matches!(*self, AsteroidSize::Big)
     }
+    pub fn points(&self) -> u32 {
+        match *self {
+            AsteroidSize::Small => POINTS_ASTEROID_SMALL,
+            AsteroidSize::Medium => POINTS_ASTEROID_MEDIUM,
+            AsteroidSize::Big => POINTS_ASTEROID_BIG,
+        }
+    }
 }
 
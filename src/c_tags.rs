@@ -11,4 +11,18 @@ pub struct Shield;
 #[derive(Component)]
 pub struct Bullet;
 #[derive(Component)]
-pub struct CameraWorld;
\ No newline at end of file
+pub struct CameraWorld;
+#[derive(Component)]
+pub struct Background;
+#[derive(Component)]
+pub struct GameOverText;
+#[derive(Component)]
+pub struct WaveBannerText;
+#[derive(Component)]
+pub struct LoadingText;
+#[derive(Component)]
+pub struct OptionsText;
+#[derive(Component)]
+pub struct MenuRoot;
+#[derive(Component)]
+pub struct HudRoot;
\ No newline at end of file
@@ -1,10 +1,52 @@
-pub const SHIP_SPRITE: &str = "textures/ship.png";
-pub const SHIELD_SPRITE: &str = "textures/shield.png";
-pub const BACKGROUND_SPRITE: &str = "textures/background.png";
-pub const ASTEROID_1_SPRITE: &str = "textures/asteroid_1.png";
-pub const BULLET_SPRITE: &str = "textures/laser_sprites/01.png";
-pub const TEXTURE_SPRITE: &str = "textures/color_gradients.png";
+pub const SHIELD_SHADER: &str = "shaders/shield.wgsl";
+pub const BASIC_TEXTURE_SHADER: &str = "shaders/basic_texture.wgsl";
 
 pub const PI: f32 = std::f32::consts::PI;
+pub const FRAC_1_SQRT_2: f32 = std::f32::consts::FRAC_1_SQRT_2;
 pub const WINDOW_WIDTH: f32 = 1280.0;
-pub const WINDOW_HEIGHT: f32 = 720.0;
\ No newline at end of file
+pub const WINDOW_HEIGHT: f32 = 720.0;
+pub const PLAYFIELD_WIDTH: f32 = 1280.0;
+pub const PLAYFIELD_HEIGHT: f32 = 720.0;
+pub const COLLISION_GRID_CELL_SIZE: f32 = 64.0;
+pub const BENCHMARK_BODY_COUNT: usize = 2000;
+pub const BULLET_CHARGE_TIERS: usize = 3;
+
+pub const COLLISION_RULES_FILE: &str = "collision_rules.ron";
+pub const SPRITE_MANIFEST_FILE: &str = "sprites.ron";
+pub const LEVELS_FOLDER: &str = "levels";
+pub const LEVEL_LIST_FILE: &str = "campaign.levels.ron";
+pub const SETTINGS_FOLDER: &str = "cometbuster"; // In the platform config folder
+pub const SETTINGS_FILE: &str = "settings.ron";
+
+pub const SPAWN_CLEARANCE: f32 = 200.0; // Distance kept from everything else when spawning at a random position
+pub const SPAWN_TRIES: usize = 100; // Random positions tried before a crowded playfield is given up on
+pub const PENETRATION_SLOP: f32 = 0.5; // Overlap in pixels that is left alone, to avoid jitter between resting bodies
+pub const ASTEROID_FRICTION: f32 = 0.3; // Lets glancing hits between asteroids pass on some spin
+pub const ASTEROID_MAX_SPIN: f32 = 1.0; // Radians per second, either way, at spawn
+pub const ASTEROID_HULL_CORNERS: usize = 9;
+pub const ASTEROID_HULL_MIN_RADIUS: f32 = 0.75; // Fraction of Radius the corners of a hull can be pulled in
+pub const PENETRATION_CORRECTION: f32 = 0.8; // Fraction of the remaining overlap removed per bounce
+
+pub const SHIELD_ENERGY_DRAIN: f32 = 100.0; // Energy per second while the shield is up
+pub const SHIELD_MIN_ENERGY: f32 = 20.0; // The shield only goes up with more energy than this
+pub const MAX_ENERGY: f32 = 100.0;
+pub const MAX_CHARGE: f32 = 2.0;
+
+pub const STARTING_LIVES: u32 = 3; // For each player
+pub const MAX_PLAYERS: usize = 4;
+pub const GAMEPAD_DEAD_ZONE: f32 = 0.3; // How far a stick or trigger has to be pushed, from 0 to 1
+pub const PLAYER_SPAWN_SPREAD: f32 = 120.0; // Distance from the spawn point of the level, when there are several players
+pub const RESPAWN_DELAY_SECONDS: f32 = 2.0;
+pub const INVULNERABILITY_SECONDS: f32 = 3.0;
+pub const GAME_OVER_SECONDS: f32 = 3.0;
+pub const WAVE_TRANSITION_SECONDS: f32 = 2.0;
+pub const WAVE_SPEED_INCREASE: f32 = 0.1; // Per wave after the last level
+pub const DENSE_ASTEROID_MASS_FACTOR: f32 = 3.0;
+pub const POINTS_ASTEROID_BIG: u32 = 20;
+pub const POINTS_ASTEROID_MEDIUM: u32 = 50;
+pub const POINTS_ASTEROID_SMALL: u32 = 100;
+
+pub const TICKS_PER_SECOND: u64 = 60;
+pub const RNG_CONFIG_FILE: &str = "rng.ron";
+pub const REPLAY_VERSION: u32 = 2;
+pub const REPLAY_SAVE_SECONDS: u64 = 10; // How often a recording is saved while the game runs, so a crash loses little of it
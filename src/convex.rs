@@ -0,0 +1,284 @@
+use bevy::prelude::*;
+use crate::torus::Torus;
+
+// Narrow phase collision between circles and convex polygons on the playfield, using the separating axis theorem.
+// Two convex shapes overlap unless there is an axis, one of the edge normals, on which their projections don't.
+
+// The outline a body collides with, around its position
+#[derive(Clone, Debug)]
+pub enum Shape {
+    Circle(f32), // Radius
+    Polygon(Vec<Vec2>), // Corners counterclockwise, already turned to the angle of the body
+}
+
+// Where two overlapping shapes touch
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Contact {
+    pub normal: Vec2, // Points from the first shape to the second
+    pub penetration: f32, // How far the second shape has to move along the normal to stop overlapping
+    pub point: Vec2,
+}
+
+// Finds the overlap of two shapes, going over the edges of the playfield when the closest copies touch there
+pub fn contact(torus: &Torus, position_1: Vec2, shape_1: &Shape, position_2: Vec2, shape_2: &Shape) -> Option<Contact> {
+    // Work around the first shape, with the closest copy of the second
+    let delta = torus.delta(position_1, position_2);
+    let (normal, penetration, point) = match (shape_1, shape_2) {
+        (Shape::Circle(radius_1), Shape::Circle(radius_2)) => circle_circle(*radius_1, delta, *radius_2)?,
+        (Shape::Polygon(corners_1), Shape::Circle(radius_2)) => polygon_circle(corners_1, delta, *radius_2)?,
+        (Shape::Circle(radius_1), Shape::Polygon(corners_2)) => {
+            let (normal, penetration, point) = polygon_circle(corners_2, -delta, *radius_1)?;
+            (-normal, penetration, point + delta)
+        }
+        (Shape::Polygon(corners_1), Shape::Polygon(corners_2)) => {
+            let corners_2: Vec<Vec2> = corners_2.iter().map(|corner| *corner + delta).collect();
+            polygon_polygon(corners_1, &corners_2)?
+        }
+    };
+    Some(Contact { normal, penetration, point: torus.wrap(position_1 + point) })
+}
+
+// Corners turned by an angle in radians, the same way the sprite is
+pub fn rotate(corners: &[Vec2], angle: f32) -> Vec<Vec2> {
+    let rotation = Vec2::from_angle(angle);
+    corners.iter().map(|corner| rotation.rotate(*corner)).collect()
+}
+
+// The part of a convex outline around the origin that lies within half_angle of direction, cut along lines from the origin.
+// Counterclockwise, with the origin as one of its corners.
+pub fn cut_piece(corners: &[Vec2], direction: Vec2, half_angle: f32) -> Vec<Vec2> {
+    let mut points = vec![Vec2::ZERO];
+    for side in [-half_angle, half_angle] {
+        points.extend(outline_towards(corners, Vec2::from_angle(side).rotate(direction)));
+    }
+    points.extend(corners.iter().filter(|corner| direction.angle_between(**corner).abs() <= half_angle));
+    convex_hull(&points)
+}
+
+// Where a ray from the origin crosses a counterclockwise outline around it
+fn outline_towards(corners: &[Vec2], direction: Vec2) -> Option<Vec2> {
+    corners.iter().zip(corners.iter().cycle().skip(1)).find_map(|(from, to)| {
+        // The ray leaves through the edge going from its right side to its left
+        let (side_from, side_to) = (direction.perp_dot(*from), direction.perp_dot(*to));
+        (side_from <= 0.0 && side_to > 0.0).then(|| *from + (*to - *from) * side_from / (side_from - side_to))
+    })
+}
+
+// Smallest convex polygon around the points, counterclockwise (Andrew's monotone chain)
+pub fn convex_hull(points: &[Vec2]) -> Vec<Vec2> {
+    let mut sorted = points.to_vec();
+    sorted.sort_by(|a, b| a.x.total_cmp(&b.x).then(a.y.total_cmp(&b.y)));
+    sorted.dedup();
+    if sorted.len() < 3 {
+        return sorted;
+    }
+    let mut hull: Vec<Vec2> = Vec::new();
+    // Lower half from left to right, then upper half back, only ever turning left
+    for pass in [sorted.clone(), sorted.into_iter().rev().collect()] {
+        let start = hull.len();
+        for point in pass {
+            while hull.len() >= start + 2 && (hull[hull.len() - 1] - hull[hull.len() - 2]).perp_dot(point - hull[hull.len() - 2]) <= 0.0 {
+                hull.pop();
+            }
+            hull.push(point);
+        }
+        hull.pop(); // The last point starts the other half
+    }
+    hull
+}
+
+fn circle_circle(radius_1: f32, delta: Vec2, radius_2: f32) -> Option<(Vec2, f32, Vec2)> {
+    let distance = delta.length();
+    let penetration = radius_1 + radius_2 - distance;
+    if penetration <= 0.0 {
+        return None;
+    }
+    // Circles on top of each other get an arbitrary normal, instead of a NaN one
+    let normal = if distance > 0.0 { delta / distance } else { Vec2::X };
+    Some((normal, penetration, normal * radius_1))
+}
+
+fn polygon_circle(corners: &[Vec2], center: Vec2, radius: f32) -> Option<(Vec2, f32, Vec2)> {
+    // Besides the edge normals, a circle can only be separated from a polygon along the line to its closest corner
+    let closest_corner = corners.iter().min_by(|a, b| a.distance_squared(center).total_cmp(&b.distance_squared(center)))?;
+    let corner_axis = (center - *closest_corner).try_normalize();
+    let (normal, penetration) = separating_axis_overlap(
+        edge_normals(corners).chain(corner_axis),
+        |axis| projection(corners, axis),
+        |axis| (center.dot(axis) - radius, center.dot(axis) + radius),
+    )?;
+    Some((normal, penetration, center - normal * radius))
+}
+
+fn polygon_polygon(corners_1: &[Vec2], corners_2: &[Vec2]) -> Option<(Vec2, f32, Vec2)> {
+    let (normal, penetration) = separating_axis_overlap(
+        edge_normals(corners_1).chain(edge_normals(corners_2)),
+        |axis| projection(corners_1, axis),
+        |axis| projection(corners_2, axis),
+    )?;
+    // The corner of the second polygon reaching deepest into the first one
+    let point = corners_2.iter().copied().min_by(|a, b| a.dot(normal).total_cmp(&b.dot(normal)))?;
+    Some((normal, penetration, point))
+}
+
+// The axis with the smallest overlap of the two projections, pointing from the first shape to the second.
+// None as soon as one axis separates them.
+fn separating_axis_overlap(
+    axes: impl Iterator<Item = Vec2>,
+    project_1: impl Fn(Vec2) -> (f32, f32),
+    project_2: impl Fn(Vec2) -> (f32, f32),
+) -> Option<(Vec2, f32)> {
+    let mut smallest: Option<(Vec2, f32)> = None;
+    for axis in axes {
+        let (min_1, max_1) = project_1(axis);
+        let (min_2, max_2) = project_2(axis);
+        // The second shape is on the positive side of the first when pushing it that way is shorter
+        let (normal, overlap) = if max_1 - min_2 <= max_2 - min_1 { (axis, max_1 - min_2) } else { (-axis, max_2 - min_1) };
+        if overlap <= 0.0 {
+            return None;
+        }
+        if smallest.iter().all(|(_, smallest_overlap)| overlap < *smallest_overlap) {
+            smallest = Some((normal, overlap));
+        }
+    }
+    smallest
+}
+
+// Outward normals of a counterclockwise polygon
+fn edge_normals(corners: &[Vec2]) -> impl Iterator<Item = Vec2> + '_ {
+    corners.iter().zip(corners.iter().cycle().skip(1))
+    .filter_map(|(from, to)| (*from - *to).perp().try_normalize())
+}
+
+fn projection(corners: &[Vec2], axis: Vec2) -> (f32, f32) {
+    corners.iter().fold((f32::INFINITY, f32::NEG_INFINITY), |(min, max), corner| {
+        let along = corner.dot(axis);
+        (min.min(along), max.max(along))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn torus() -> Torus {
+        Torus::new(Vec2::new(1280.0, 720.0))
+    }
+
+    fn position() -> impl Strategy<Value = Vec2> {
+        (-640.0f32..640.0, -360.0f32..360.0).prop_map(|(x, y)| Vec2::new(x, y))
+    }
+
+    // Random points around the origin, at least three of them
+    fn points() -> impl Strategy<Value = Vec<Vec2>> {
+        prop::collection::vec((-100.0f32..100.0, -100.0f32..100.0).prop_map(|(x, y)| Vec2::new(x, y)), 3..20)
+    }
+
+    fn regular_polygon(corners: usize, radius: f32) -> Vec<Vec2> {
+        (0..corners).map(|i| Vec2::from_angle(i as f32 * 2.0 * std::f32::consts::PI / corners as f32) * radius).collect()
+    }
+
+    fn square(half_size: f32) -> Shape {
+        Shape::Polygon(vec![
+            Vec2::new(-half_size, -half_size), Vec2::new(half_size, -half_size),
+            Vec2::new(half_size, half_size), Vec2::new(-half_size, half_size),
+        ])
+    }
+
+    proptest! {
+        #[test]
+        fn convex_hull_is_convex_and_holds_every_point(points in points()) {
+            let hull = convex_hull(&points);
+            prop_assume!(hull.len() >= 3);
+            for (i, corner) in hull.iter().enumerate() {
+                let edge = hull[(i + 1) % hull.len()] - *corner;
+                // Every point is on the inside, left of every counterclockwise edge
+                for point in points.iter() {
+                    prop_assert!(edge.perp_dot(*point - *corner) >= -1e-2);
+                }
+            }
+        }
+
+        #[test]
+        fn cut_piece_stays_inside_the_outline(points in points(), direction in 0.0f32..6.3, half_angle in 0.3f32..1.5) {
+            let outline = convex_hull(&points);
+            // Only outlines around the origin can be cut from it
+            prop_assume!(outline.len() >= 3 && edge_normals(&outline).zip(outline.iter()).all(|(normal, corner)| corner.dot(normal) > 1.0));
+            let piece = cut_piece(&outline, Vec2::from_angle(direction), half_angle);
+            prop_assert!(piece.len() >= 3);
+            for (i, corner) in outline.iter().enumerate() {
+                let edge = outline[(i + 1) % outline.len()] - *corner;
+                for piece_corner in piece.iter() {
+                    prop_assert!(edge.perp_dot(*piece_corner - *corner) >= -1e-2);
+                    prop_assert!(piece_corner.length() < 1e-3 || Vec2::from_angle(direction).angle_between(*piece_corner).abs() <= half_angle + 1e-3);
+                }
+            }
+        }
+
+        #[test]
+        fn fine_polygons_collide_like_circles(position_1 in position(), offset in (-200.0f32..200.0, -200.0f32..200.0)) {
+            // Polygons with many corners are nearly circles, and should find nearly the same contact
+            let position_2 = position_1 + Vec2::new(offset.0, offset.1);
+            let circles = contact(&torus(), position_1, &Shape::Circle(60.0), position_2, &Shape::Circle(40.0));
+            let polygons = contact(&torus(), position_1, &Shape::Polygon(regular_polygon(128, 60.0)), position_2, &Shape::Polygon(regular_polygon(128, 40.0)));
+            let mixed = contact(&torus(), position_1, &Shape::Polygon(regular_polygon(128, 60.0)), position_2, &Shape::Circle(40.0));
+            if let Some(circles) = circles {
+                prop_assume!(circles.penetration > 1.0);
+                for other in [polygons, mixed] {
+                    let other = other.unwrap();
+                    prop_assert!(other.normal.dot(circles.normal) > 0.95);
+                    prop_assert!((other.penetration - circles.penetration).abs() < 1.0);
+                }
+            }
+            else {
+                prop_assert!(polygons.iter().all(|contact| contact.penetration < 1.0));
+            }
+        }
+
+        #[test]
+        fn contact_is_the_same_from_either_side(position_1 in position(), offset in (-80.0f32..80.0, -80.0f32..80.0), angle in 0.0f32..6.3) {
+            let position_2 = position_1 + Vec2::new(offset.0, offset.1);
+            let shape_1 = Shape::Polygon(rotate(&regular_polygon(5, 50.0), angle));
+            let shapes_2 = [Shape::Circle(30.0), square(30.0)];
+            for shape_2 in shapes_2.iter() {
+                let forward = contact(&torus(), position_1, &shape_1, position_2, shape_2);
+                let backward = contact(&torus(), position_2, shape_2, position_1, &shape_1);
+                prop_assert_eq!(forward.is_some(), backward.is_some());
+                if let (Some(forward), Some(backward)) = (forward, backward) {
+                    prop_assert!((forward.normal + backward.normal).length() < 1e-3);
+                    prop_assert!((forward.penetration - backward.penetration).abs() < 1e-2);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn third_of_a_square_is_cut_along_lines_from_the_center() {
+        let square = [Vec2::new(-10.0, -10.0), Vec2::new(10.0, -10.0), Vec2::new(10.0, 10.0), Vec2::new(-10.0, 10.0)];
+        let piece = cut_piece(&square, Vec2::X, std::f32::consts::PI / 3.0);
+        // The center, the two right corners, and where the cuts at 60 degrees leave through the top and bottom edges
+        let cut = 10.0 / 3.0f32.sqrt();
+        let expected = [Vec2::ZERO, Vec2::new(cut, -10.0), Vec2::new(10.0, -10.0), Vec2::new(10.0, 10.0), Vec2::new(cut, 10.0)];
+        assert_eq!(piece.len(), expected.len());
+        for corner in expected {
+            assert!(piece.iter().any(|piece_corner| piece_corner.distance(corner) < 1e-3));
+        }
+    }
+
+    #[test]
+    fn squares_touch_across_the_edge() {
+        // One square on each side of the right/left edge, overlapping by 10
+        let contact = contact(&torus(), Vec2::new(630.0, 0.0), &square(20.0), Vec2::new(-620.0, 0.0), &square(20.0)).unwrap();
+        assert!((contact.normal - Vec2::X).length() < 1e-5);
+        assert!((contact.penetration - 10.0).abs() < 1e-3);
+        assert!(contact.point.x > 600.0 || contact.point.x < -600.0);
+    }
+
+    #[test]
+    fn circle_in_the_gap_of_a_corner_does_not_touch() {
+        // Inside the bounding circle of the square, but past its corner
+        assert!(contact(&torus(), Vec2::ZERO, &square(20.0), Vec2::new(27.0, 27.0), &Shape::Circle(8.0)).is_none());
+        assert!(contact(&torus(), Vec2::ZERO, &square(20.0), Vec2::new(25.0, 0.0), &Shape::Circle(8.0)).is_some());
+    }
+}
\ No newline at end of file
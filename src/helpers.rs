@@ -1,136 +1,328 @@
-use bevy::prelude::*;
+use bevy::{
+    prelude::*,
+    ecs::system::EntityCommands,
+};
 use rand::Rng;
 
 use crate::consts::*;
-use crate::c_movement_and_collisions::Velocity;
-use crate::c_screenshake::ScreenShake;
-use crate::c_lifetime_spawntime::{Lifetime, SpawnTime};
+use crate::c_bundles::ShieldBundle;
+use crate::c_lifetime_spawntime::ticks_from_seconds;
+use crate::c_movement_and_collisions::{Angle, AngularVelocity, CollisionType};
+use crate::c_playfield::Playfield;
+use crate::c_shipstats::Invulnerable;
+use crate::torus::Torus;
 
 // Returns a random f32 from FIRST_ARGUMENT to SECOND_ARGUMENT, not including SECOND_ARGUMENT
-pub fn rf32(low: f32, high: f32) -> f32 {
-    let mut rng = rand::thread_rng();
+pub fn rf32(rng: &mut impl Rng, low: f32, high: f32) -> f32 {
     return rng.gen::<f32>() * (high - low) + low;
 }
 
-// Returns a random position that is not currently occupied by an entity with a CollsionType component
+// Returns the value following a command line argument, like the file name in "--replay run.ron"
+pub fn arg_value(name: &str) -> Option<String> {
+    let mut args = std::env::args().skip_while(|arg| arg != name);
+    args.next()?;
+    args.next()
+}
+
+// Returns a random position inside the playfield
+pub fn random_position(playfield: &Playfield, rng: &mut impl Rng) -> Vec2 {
+    Vec2::new(
+        rf32(rng, -playfield.width / 2.0, playfield.width / 2.0),
+        rf32(rng, -playfield.height / 2.0, playfield.height / 2.0),
+    )
+}
+
+// Returns a random position at least SPAWN_CLEARANCE away from all the given positions.
+// Returns None when the playfield is too crowded to find one, and leaves it to the caller to deal with the overlap.
 pub fn random_free_position(
-    position_vec: &Vec<Vec2>
-) -> Vec2 {
-    let mut position_free = false;
-    let mut x_pos: f32 = 0.0;
-    let mut y_pos: f32 = 0.0;
-    while position_free == false {
-        position_free = true;
-        x_pos = rf32(0.0, WINDOW_WIDTH);
-        y_pos = rf32(0.0, WINDOW_HEIGHT);
-        for position in position_vec.iter() {
-            if shortest_distance(position.x, position.y, x_pos, y_pos) < 200.0 {
-                position_free = false;
-            }
-        }
+    playfield: &Playfield,
+    rng: &mut impl Rng,
+    positions: &[Vec2],
+) -> Option<Vec2> {
+    let torus = playfield.torus();
+    (0..SPAWN_TRIES)
+    .map(|_| random_position(playfield, rng))
+    .find(|candidate| positions.iter().all(|position| torus.distance(*position, *candidate) >= SPAWN_CLEARANCE))
+}
+
+// Shields a ship for a moment, like after a respawn, without cutting short the invulnerability it already has.
+// A shield the player is holding up already covers the ship.
+pub fn shield_for_a_moment(ship: &mut EntityCommands, sim_tick: u64, invulnerable: Option<&Invulnerable>, collision_type: CollisionType) {
+    let until = sim_tick + ticks_from_seconds(INVULNERABILITY_SECONDS);
+    ship.insert(Invulnerable(until.max(invulnerable.map_or(0, |invulnerable| invulnerable.0))));
+    if collision_type != CollisionType::Shield {
+        ship
+        .insert(CollisionType::Shield)
+        .with_children(|parent| {
+            parent.spawn(ShieldBundle::default());
+        });
     }
-    return Vec2::new(x_pos, y_pos);
 }
 
-// Returns the closest position of entity 2 from entity 1, taking edge looping into account
-pub fn closest_position (x1: f32, y1: f32, x2: f32, y2: f32) -> Vec2 {
-    let new_x2: f32;
-    let new_y2: f32;
+// A random facing and spin, for asteroids to not all look the same way
+pub fn random_rotation(rng: &mut impl Rng) -> (Angle, AngularVelocity) {
+    (
+        Angle(rf32(rng, 0.0, 2.0 * PI)),
+        AngularVelocity(rf32(rng, -ASTEROID_MAX_SPIN, ASTEROID_MAX_SPIN)),
+    )
+}
 
-    if (x2 + WINDOW_WIDTH - x1).abs() < (x2 - x1).abs() { new_x2 = x2 + WINDOW_WIDTH; }
-    else if (x2 - WINDOW_WIDTH - x1).abs() < (x2 - x1).abs() { new_x2 = x2 - WINDOW_WIDTH; }
-    else { new_x2 = x2; }
+// A body taking part in a bounce, with its position and velocity updated in place
+pub struct BounceBody {
+    pub position: Vec2,
+    pub velocity: Vec2,
+    pub mass: f32,
+    pub radius: f32,
+    pub restitution: f32,
+    pub friction: f32,
+    pub angular_velocity: f32,
+    pub inverse_inertia: f32, // Zero for bodies that don't spin
+}
 
-    if (y2 + WINDOW_WIDTH - y1).abs() < (y2 - y1).abs() { new_y2 = y2 + WINDOW_WIDTH; }
-    else if (y2 - WINDOW_WIDTH - y1).abs() < (y2 - y1).abs() { new_y2 = y2 - WINDOW_WIDTH; }
-    else { new_y2 = y2; }
+impl BounceBody {
+    // Inverse moment of inertia of a solid disc
+    pub fn disc_inverse_inertia(mass: f32, radius: f32) -> f32 {
+        2.0 / (mass * radius * radius)
+    }
+}
 
-    return Vec2::new(new_x2, new_y2);
+// Resolves a collision between two bodies with normal and friction impulses, then pushes them apart if they overlap.
+// Returns the impulse applied to body 2 (body 1 gets the opposite), or None if the bodies are moving apart and don't bounce.
+pub fn collision_bounce(torus: &Torus, body_1: &mut BounceBody, body_2: &mut BounceBody) -> Option<Vec2> {
+    let delta = torus.delta(body_1.position, body_2.position);
+    let distance = delta.length();
+    // Bodies on top of each other get an arbitrary normal, instead of a NaN one
+    let normal = if distance > 0.0 { delta / distance } else { Vec2::X };
+    collision_bounce_along(torus, body_1, body_2, normal, body_1.radius + body_2.radius - distance)
 }
 
-// Returns the shortest distance between entities, taking edge looping into account
-pub fn shortest_distance (x1: f32, y1: f32, x2: f32, y2: f32) -> f32 {
-    let x_dist_1 = (x1 - x2).abs();
-    let x_dist_2 = (x1 - (x2 + WINDOW_WIDTH)).abs();
-    let x_dist_3 = (x1 - (x2 - WINDOW_WIDTH)).abs();
-    let x_min = f32::min(x_dist_1, f32::min(x_dist_2, x_dist_3));
+// The same bounce, for bodies whose contact normal (from body 1 to body 2) and overlap were found by the narrow phase
+pub fn collision_bounce_along(torus: &Torus, body_1: &mut BounceBody, body_2: &mut BounceBody, normal: Vec2, penetration: f32) -> Option<Vec2> {
+    let tangent = normal.perp();
+
+    let inverse_mass_1 = 1.0 / body_1.mass;
+    let inverse_mass_2 = 1.0 / body_2.mass;
+    let inverse_mass_sum = inverse_mass_1 + inverse_mass_2;
+
+    // Push overlapping bodies apart along the normal, lighter bodies move more.
+    // A body pushed over an edge comes back in from the opposite one.
+    if penetration > PENETRATION_SLOP {
+        let correction = normal * (penetration - PENETRATION_SLOP) * PENETRATION_CORRECTION / inverse_mass_sum;
+        body_1.position = torus.wrap(body_1.position - correction * inverse_mass_1);
+        body_2.position = torus.wrap(body_2.position + correction * inverse_mass_2);
+    }
+
+    // Only bounce bodies that are moving towards each other
+    let relative_velocity = body_2.velocity - body_1.velocity;
+    let normal_speed = relative_velocity.dot(normal);
+    if normal_speed >= 0.0 {
+        return None;
+    }
+
+    let restitution = (body_1.restitution * body_2.restitution).sqrt();
+    let normal_impulse = -(1.0 + restitution) * normal_speed / inverse_mass_sum;
+
+    // Coulomb friction, never more than what stops the sliding completely.
+    // The surfaces slide past each other faster or slower depending on the spin of the bodies, and the friction passes spin between them.
+    let friction = (body_1.friction * body_2.friction).sqrt();
+    let sliding_speed = relative_velocity.dot(tangent) - body_1.angular_velocity * body_1.radius - body_2.angular_velocity * body_2.radius;
+    let inverse_tangent_mass = inverse_mass_sum
+        + body_1.radius * body_1.radius * body_1.inverse_inertia
+        + body_2.radius * body_2.radius * body_2.inverse_inertia;
+    let tangent_impulse = (-sliding_speed / inverse_tangent_mass).clamp(-friction * normal_impulse, friction * normal_impulse);
 
-    let y_dist_1 = (y1 - y2).abs();
-    let y_dist_2 = (y1 - (y2 + WINDOW_HEIGHT)).abs();
-    let y_dist_3 = (y1 - (y2 - WINDOW_HEIGHT)).abs();
-    let y_min = f32::min(y_dist_1, f32::min(y_dist_2, y_dist_3));
+    let impulse = normal * normal_impulse + tangent * tangent_impulse;
+    body_1.velocity -= impulse * inverse_mass_1;
+    body_2.velocity += impulse * inverse_mass_2;
+    body_1.angular_velocity -= body_1.radius * tangent_impulse * body_1.inverse_inertia;
+    body_2.angular_velocity -= body_2.radius * tangent_impulse * body_2.inverse_inertia;
 
-    return x_min.hypot(y_min);
+    Some(impulse)
 }
 
-// Returns the new X and Y velocities of entities after they bounce
-pub fn collision_bounce(
-    commands: &mut Commands,
-    // Get position, velocity and mass of both entities
-    t1: Vec3,
-    v1: &mut Velocity,
-    m1: f32,
-    mut t2: Vec3,
-    v2: &mut Velocity,
-    m2: f32,
-    time: &Res<Time>,
-    r1: f32,
-) {
-    // Check if the entities are moving towards each other
-    if shortest_distance(
-        t1.x + time.delta_seconds() * v1.x,
-        t1.y + time.delta_seconds() * v1.y,
-        t2.x + time.delta_seconds() * v2.x,
-        t2.y + time.delta_seconds() * v2.y,
-    ) <
-        shortest_distance(t1.x, t1.y, t2.x, t2.y)
-    {
-        if (t2.x + WINDOW_WIDTH - t1.x).abs() < (t2.x - t1.x).abs() { t2.x += WINDOW_WIDTH; }
-        if (t2.x - WINDOW_WIDTH - t1.x).abs() < (t2.x - t1.x).abs() { t2.x -= WINDOW_WIDTH; }
-        if (t2.y + WINDOW_HEIGHT - t1.y).abs() < (t2.y - t1.y).abs() { t2.y += WINDOW_HEIGHT; }
-        if (t2.y - WINDOW_HEIGHT - t1.y).abs() < (t2.y - t1.y).abs() { t2.y -= WINDOW_HEIGHT; }
-
-        let mut th1 = (v1.y / v1.x).atan(); // Theta, ent 1
-        if v1.x < 0.0 { th1 += PI; } // .atan() can only calculate an angle, not which direction along that angle
-        let mut th2 = (v2.y / v2.x).atan(); // Theta, ent 2
-        if v2.x < 0.0 { th2 += PI; } // .atan() can only calculate an angle, not which direction along that angle
-        let vt1 = v1.x.hypot(v1.y).abs(); // Velocity Total, ent 1
-        let vt2 = v2.x.hypot(v2.y).abs(); // Velocity Total, ent 2
-        let mut th12 = ((t2.y-t1.y)/(t2.x-t1.x)).atan(); // Theta between the entities
-        if t2.x < t1.x { th12 += PI; } // .atan() can only calculate an angle, not which direction along that angle
-
-        let v1_start = v1.clone();
-
-        let loss_factor: f32 = 0.95;
-        // https://en.wikipedia.org/wiki/Elastic_collision - Two-dimensional collision with two moving objects
-        v1.x = loss_factor * (vt1 * (th1-th12).cos() * ( m1 - m2 ) + 2.0 * m2 * vt2 * ( th2 - th12 ).cos() ) / ( m1 + m2 ) * th12.cos() + vt1 * ( th1 - th12 ).sin() * ( th12 + PI / 2.0 ).cos();
-        v1.y = loss_factor * (vt1 * (th1-th12).cos() * ( m1 - m2 ) + 2.0 * m2 * vt2 * ( th2 - th12 ).cos() ) / ( m1 + m2 ) * th12.sin() + vt1 * ( th1 - th12 ).sin() * ( th12 + PI / 2.0 ).sin();
-        v2.x = loss_factor * (vt2 * (th2-th12).cos() * ( m2 - m1 ) + 2.0 * m1 * vt1 * ( th1 - th12 ).cos() ) / ( m2 + m1 ) * th12.cos() + vt2 * ( th2 - th12 ).sin() * ( th12 + PI / 2.0 ).cos();
-        v2.y = loss_factor * (vt2 * (th2-th12).cos() * ( m2 - m1 ) + 2.0 * m1 * vt1 * ( th1 - th12 ).cos() ) / ( m2 + m1 ) * th12.sin() + vt2 * ( th2 - th12 ).sin() * ( th12 + PI / 2.0 ).sin();
-
-        let change_of_momentum: f32 = m1 * (v1_start.x - v1.x).hypot(v1_start.y - v1.y);
-        commands.spawn(ScreenShake{amplitude: (0.0 + change_of_momentum / 5000.0).min(10.0), ..Default::default()});
-
-        let contact_point: Vec2 = Vec2::new(t1.x + th12.cos() * r1, t1.y + th12.sin() * r1);
-        commands.spawn(SpriteBundle {
-            sprite: Sprite {
-                color: Color::rgb(1.0, 1.0, 1.0),
-                flip_x: false,
-                flip_y: false,
-                custom_size: Some(Vec2::new(5.0,5.0)),
-                ..Default::default()
-            },
-            transform: Transform {
-                translation: Vec3::new(
-                    contact_point.x,
-                    contact_point.y,
-                    100.0,
-                ),
-                ..Default::default()
-            },
-            ..Default::default()
-        })
-        .insert(SpawnTime(instant::Instant::now()))
-        .insert(Lifetime(instant::Duration::from_secs_f32(1.0)));
-    }
-}
\ No newline at end of file
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+    use rand::SeedableRng;
+    use rand_chacha::ChaCha8Rng;
+
+    fn body(position: Vec2, velocity: Vec2, mass: f32, radius: f32, restitution: f32, friction: f32) -> BounceBody {
+        BounceBody { position, velocity, mass, radius, restitution, friction, angular_velocity: 0.0, inverse_inertia: 0.0 }
+    }
+
+    fn spinning_body(position: Vec2, velocity: Vec2, mass: f32, radius: f32, angular_velocity: f32) -> BounceBody {
+        BounceBody {
+            angular_velocity,
+            inverse_inertia: BounceBody::disc_inverse_inertia(mass, radius),
+            ..body(position, velocity, mass, radius, 0.95, ASTEROID_FRICTION)
+        }
+    }
+
+    fn total_energy(body_1: &BounceBody, body_2: &BounceBody) -> f32 {
+        let rotational = |body: &BounceBody| 0.5 * body.angular_velocity * body.angular_velocity / body.inverse_inertia;
+        kinetic_energy(body_1, body_2) + rotational(body_1) + rotational(body_2)
+    }
+
+    fn momentum(body_1: &BounceBody, body_2: &BounceBody) -> Vec2 {
+        body_1.velocity * body_1.mass + body_2.velocity * body_2.mass
+    }
+
+    fn kinetic_energy(body_1: &BounceBody, body_2: &BounceBody) -> f32 {
+        0.5 * body_1.mass * body_1.velocity.length_squared() + 0.5 * body_2.mass * body_2.velocity.length_squared()
+    }
+
+    fn position() -> impl Strategy<Value = Vec2> {
+        (-PLAYFIELD_WIDTH / 2.0..PLAYFIELD_WIDTH / 2.0, -PLAYFIELD_HEIGHT / 2.0..PLAYFIELD_HEIGHT / 2.0).prop_map(|(x, y)| Vec2::new(x, y))
+    }
+
+    fn velocity() -> impl Strategy<Value = Vec2> {
+        (-500.0f32..500.0, -500.0f32..500.0).prop_map(|(x, y)| Vec2::new(x, y))
+    }
+
+    // Positions around the corner of the playfield, where a push can cross both edges at once
+    fn position_near_corner() -> impl Strategy<Value = Vec2> {
+        (-60.0f32..60.0, -60.0f32..60.0).prop_map(|(x, y)| Playfield::default().torus().wrap(Vec2::new(PLAYFIELD_WIDTH / 2.0 + x, PLAYFIELD_HEIGHT / 2.0 + y)))
+    }
+
+    fn inside_playfield(position: Vec2) -> bool {
+        position.x.abs() <= PLAYFIELD_WIDTH / 2.0 && position.y.abs() <= PLAYFIELD_HEIGHT / 2.0
+    }
+
+    // Velocities along one axis, or none at all, which broke the atan based bounce
+    fn axis_aligned_velocity() -> impl Strategy<Value = Vec2> {
+        prop_oneof![
+            Just(Vec2::ZERO),
+            (-500.0f32..500.0).prop_map(|x| Vec2::new(x, 0.0)),
+            (-500.0f32..500.0).prop_map(|y| Vec2::new(0.0, y)),
+        ]
+    }
+
+    proptest! {
+        #[test]
+        fn free_position_keeps_clear_of_everything(
+            positions in prop::collection::vec(position(), 0..12),
+            seed in any::<u64>(),
+        ) {
+            let playfield = Playfield::default();
+            let mut rng = ChaCha8Rng::seed_from_u64(seed);
+            if let Some(free) = random_free_position(&playfield, &mut rng, &positions) {
+                prop_assert!(inside_playfield(free));
+                for position in positions.iter() {
+                    prop_assert!(playfield.torus().distance(*position, free) >= SPAWN_CLEARANCE);
+                }
+            }
+        }
+
+        #[test]
+        fn bounce_conserves_momentum(
+            position_1 in position(), offset in velocity(), velocity_1 in velocity(), velocity_2 in velocity(),
+            mass_1 in 1.0f32..100.0, mass_2 in 1.0f32..100.0,
+            restitution_1 in 0.0f32..1.0, restitution_2 in 0.0f32..1.0, friction_1 in 0.0f32..1.0, friction_2 in 0.0f32..1.0,
+        ) {
+            let position_2 = position_1 + offset.clamp_length_max(100.0);
+            let mut body_1 = body(position_1, velocity_1, mass_1, 60.0, restitution_1, friction_1);
+            let mut body_2 = body(position_2, velocity_2, mass_2, 60.0, restitution_2, friction_2);
+            let momentum_before = momentum(&body_1, &body_2);
+            let energy_before = kinetic_energy(&body_1, &body_2);
+
+            collision_bounce(&Playfield::default().torus(), &mut body_1, &mut body_2);
+
+            let tolerance = 1e-3 * (1.0 + momentum_before.length() + velocity_1.length() * mass_1 + velocity_2.length() * mass_2);
+            prop_assert!((momentum(&body_1, &body_2) - momentum_before).length() < tolerance);
+            // Restitution below one can only take energy out
+            prop_assert!(kinetic_energy(&body_1, &body_2) <= energy_before * (1.0 + 1e-4) + 1e-3);
+        }
+
+        #[test]
+        fn bounce_never_produces_nan(
+            position_1 in position(), offset in axis_aligned_velocity(), velocity_1 in axis_aligned_velocity(), velocity_2 in axis_aligned_velocity(),
+            mass_1 in 1.0f32..100.0, mass_2 in 1.0f32..100.0,
+        ) {
+            // Offsets are axis aligned too, including bodies exactly on top of each other
+            let position_2 = position_1 + offset.clamp_length_max(100.0);
+            let mut body_1 = body(position_1, velocity_1, mass_1, 60.0, 0.95, 0.5);
+            let mut body_2 = body(position_2, velocity_2, mass_2, 60.0, 0.95, 0.5);
+
+            collision_bounce(&Playfield::default().torus(), &mut body_1, &mut body_2);
+
+            prop_assert!(body_1.velocity.is_finite() && body_2.velocity.is_finite());
+            prop_assert!(body_1.position.is_finite() && body_2.position.is_finite());
+        }
+
+        #[test]
+        fn bounce_separates_bodies_across_the_edge(
+            y in -PLAYFIELD_HEIGHT / 2.0..PLAYFIELD_HEIGHT / 2.0, overlap in 1.0f32..20.0, mass_1 in 1.0f32..100.0, mass_2 in 1.0f32..100.0,
+        ) {
+            // Two bodies touching across the left/right playfield edge
+            let mut body_1 = body(Vec2::new(PLAYFIELD_WIDTH / 2.0 - 10.0, y), Vec2::ZERO, mass_1, 20.0, 0.95, 0.0);
+            let mut body_2 = body(Vec2::new(-PLAYFIELD_WIDTH / 2.0 + 30.0 - overlap, y), Vec2::ZERO, mass_2, 20.0, 0.95, 0.0);
+
+            collision_bounce(&Playfield::default().torus(), &mut body_1, &mut body_2);
+
+            // Body 1 is pushed left and body 2 right, not towards each other through the whole playfield
+            prop_assert!(body_1.position.x < PLAYFIELD_WIDTH / 2.0 - 10.0);
+            prop_assert!(body_2.position.x > -PLAYFIELD_WIDTH / 2.0 + 30.0 - overlap);
+            prop_assert!(Playfield::default().torus().distance(body_1.position, body_2.position) > 40.0 - overlap);
+        }
+
+        #[test]
+        fn bounce_keeps_bodies_inside_the_playfield(
+            position_1 in position_near_corner(), offset in velocity(), velocity_1 in velocity(), velocity_2 in velocity(),
+            mass_1 in 1.0f32..100.0, mass_2 in 1.0f32..100.0,
+        ) {
+            let offset = offset.clamp_length_max(100.0);
+            let position_2 = Playfield::default().torus().wrap(position_1 + offset);
+            let mut body_1 = body(position_1, velocity_1, mass_1, 60.0, 0.95, 0.5);
+            let mut body_2 = body(position_2, velocity_2, mass_2, 60.0, 0.95, 0.5);
+
+            collision_bounce(&Playfield::default().torus(), &mut body_1, &mut body_2);
+
+            prop_assert!(inside_playfield(body_1.position), "body 1 was pushed out to {:?}", body_1.position);
+            prop_assert!(inside_playfield(body_2.position), "body 2 was pushed out to {:?}", body_2.position);
+        }
+
+        #[test]
+        fn spin_transfer_never_adds_energy(
+            position_1 in position(), offset in velocity(), velocity_1 in velocity(), velocity_2 in velocity(),
+            mass_1 in 1.0f32..100.0, mass_2 in 1.0f32..100.0, spin_1 in -5.0f32..5.0, spin_2 in -5.0f32..5.0,
+        ) {
+            let position_2 = position_1 + offset.clamp_length_max(100.0);
+            let mut body_1 = spinning_body(position_1, velocity_1, mass_1, 60.0, spin_1);
+            let mut body_2 = spinning_body(position_2, velocity_2, mass_2, 60.0, spin_2);
+            let momentum_before = momentum(&body_1, &body_2);
+            let energy_before = total_energy(&body_1, &body_2);
+
+            collision_bounce(&Playfield::default().torus(), &mut body_1, &mut body_2);
+
+            let tolerance = 1e-3 * (1.0 + momentum_before.length() + velocity_1.length() * mass_1 + velocity_2.length() * mass_2);
+            prop_assert!((momentum(&body_1, &body_2) - momentum_before).length() < tolerance);
+            prop_assert!(total_energy(&body_1, &body_2) <= energy_before * (1.0 + 1e-4) + 1e-3);
+        }
+    }
+
+    #[test]
+    fn crowded_playfield_has_no_free_position() {
+        let playfield = Playfield::default();
+        let mut positions = Vec::<Vec2>::new();
+        for i in 0..(PLAYFIELD_WIDTH / SPAWN_CLEARANCE).ceil() as usize {
+            for j in 0..(PLAYFIELD_HEIGHT / SPAWN_CLEARANCE).ceil() as usize {
+                positions.push(Vec2::new(i as f32 * SPAWN_CLEARANCE, j as f32 * SPAWN_CLEARANCE));
+            }
+        }
+        assert_eq!(random_free_position(&playfield, &mut ChaCha8Rng::seed_from_u64(1), &positions), None);
+    }
+
+    #[test]
+    fn glancing_hit_spins_both_bodies() {
+        // Body 2 passes body 1 going up, touching its right side
+        let mut body_1 = spinning_body(Vec2::new(0.0, 0.0), Vec2::ZERO, 50.0, 40.0, 0.0);
+        let mut body_2 = spinning_body(Vec2::new(79.0, -10.0), Vec2::new(-50.0, 300.0), 50.0, 40.0, 0.0);
+
+        collision_bounce(&Playfield::default().torus(), &mut body_1, &mut body_2);
+
+        // The right side of body 1 is dragged up and the left side of body 2 down, turning both counterclockwise
+        assert!(body_1.angular_velocity > 0.0);
+        assert!(body_2.angular_velocity > 0.0);
+    }
+}
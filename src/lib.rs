@@ -0,0 +1,61 @@
+// The game as a library, so the game, the headless simulation and the tests can all build an App from the same plugins
+
+pub mod helpers;
+pub mod torus;
+pub mod convex;
+pub mod consts;
+pub mod c_controls;
+pub mod c_bundles;
+pub mod c_tags;
+pub mod c_events;
+pub mod c_sprites;
+pub mod c_sprite_manifest;
+pub mod c_chargelevel;
+pub mod c_lifetime_spawntime;
+pub mod c_movement_and_collisions;
+pub mod c_screenshake;
+pub mod c_shipstats;
+pub mod c_appstate;
+pub mod c_spatial_grid;
+pub mod c_collision_rules;
+pub mod c_gamerng;
+pub mod c_replay;
+pub mod c_sim_stats;
+pub mod c_score;
+pub mod c_levels;
+pub mod c_playfield;
+pub mod c_players;
+pub mod c_settings;
+pub mod c_menu;
+pub mod c_hud;
+
+pub mod material_shield;
+pub mod material_basic;
+
+pub mod s_gameplay;
+pub mod s_energy;
+pub mod s_movement;
+pub mod s_spawn_despawn;
+pub mod s_simulation;
+pub mod s_replay;
+pub mod s_collision_detection;
+pub mod s_collision_response;
+pub mod s_control;
+pub mod s_autopilot;
+pub mod s_sim_stats;
+pub mod s_score;
+pub mod s_game_over;
+pub mod s_waves;
+pub mod s_wave_banner;
+pub mod s_level_assets;
+pub mod s_sprites;
+pub mod s_screen_shake;
+pub mod s_menu;
+pub mod s_hud;
+pub mod s_pause;
+pub mod s_settings;
+pub mod s_options;
+pub mod s_setup_world;
+pub mod s_loading;
+pub mod s_benchmark;
+pub mod s_asset_stats;
\ No newline at end of file
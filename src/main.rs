@@ -14,7 +14,6 @@
 use bevy::{
     prelude::*,
     window::*,
-//    asset::AssetServerSettings,
 };
 
 //use bevy_inspector_egui::quick::WorldInspectorPlugin;
@@ -22,58 +21,31 @@ use bevy::{
 // Add instant crate in modules that need it?
 //extern crate instant; // Works exactly like the std::time counterpart on native, but uses JS performance.now() for WASM
 
-use consts::*;
-use c_events::*;
-use c_sprites::Textures;
-use c_appstate::AppState;
-
-mod helpers;
-mod consts;
-mod c_controls;
-mod c_bundles;
-mod c_tags;
-mod c_events;
-mod c_sprites;
-mod c_chargelevel;
-mod c_lifetime_spawntime;
-mod c_movement_and_collisions;
-mod c_screenshake;
-mod c_shipstats;
-mod c_appstate;
-
-mod material_shield;
-use material_shield::*;
-mod material_basic;
-use material_basic::*;
-
-mod s_energy;
-use s_energy::EnergyPlugin;
-mod s_movement;
-use s_movement::MovementPlugin;
-mod s_spawn_despawn;
-use s_spawn_despawn::SpawnDespawnPlugin;
-mod s_collision_detection;
-use s_collision_detection::CollisionDetectionPlugin;
-mod s_control;
-use s_control::ControlPlugin;
-mod s_screen_shake;
-use s_screen_shake::ScreenShakePlugin;
-mod s_pause;
-use s_pause::PausePlugin;
-mod s_setup_world;
-use s_setup_world::SetupWorldPlugin;
-
-#[derive(Event, TypePath)]
-struct EvSpawnBounceEffect{
-//    transform: Transform,
-}
+use cometbuster::{
+    consts::*,
+    material_shield::MaterialShieldPlugin,
+    material_basic::MaterialBasicPlugin,
+    s_gameplay::GameplayPlugin,
+    s_replay::ReplayPlugin,
+    s_sprites::SpritesPlugin,
+    s_screen_shake::ScreenShakePlugin,
+    s_menu::MenuPlugin,
+    s_hud::HudPlugin,
+    s_pause::PausePlugin,
+    s_settings::SettingsPlugin,
+    s_options::OptionsPlugin,
+    s_game_over::GameOverPlugin,
+    s_wave_banner::WaveBannerPlugin,
+    s_level_assets::LevelAssetsPlugin,
+    s_setup_world::SetupWorldPlugin,
+    s_loading::LoadingPlugin,
+    s_benchmark::BenchmarkPlugin,
+    s_asset_stats::AssetStatsPlugin,
+};
 
 fn main() {
     let mut app = App::new();
 
-    app.add_state::<AppState>()
-    ;
-
     app.add_plugins(DefaultPlugins.set(WindowPlugin {
         primary_window: Some(Window {
             title: "CometBuster".into(),
@@ -91,49 +63,38 @@ cursor: Cursor::new{
 }
 */
 
-// Watch for assets changes. Must be before DefaultPlugins
-//    .insert_resource(AssetServerSettings {
-//        watch_for_changes: true,
-//        ..default()
-//    })
-//    .add_plugins(DefaultPlugins)
+// Watch for assets changes: cargo run --features file_watcher
+// Edited level files are reloaded by LevelAssetsPlugin
 
 
 //    .add_plugins(WorldInspectorPlugin::new())
-    .add_plugins(EnergyPlugin)
-    .add_plugins(MovementPlugin)
-    .add_plugins(SpawnDespawnPlugin)
-    .add_plugins(CollisionDetectionPlugin)
-    .add_plugins(ControlPlugin)
+    .add_plugins(ReplayPlugin)
+    .add_plugins(GameplayPlugin)
+    .add_plugins(SpritesPlugin)
     .add_plugins(ScreenShakePlugin)
     .add_plugins(SetupWorldPlugin)
+    .add_plugins(LoadingPlugin)
     .add_plugins(MaterialShieldPlugin)
     .add_plugins(MaterialBasicPlugin)
+    .add_plugins(MenuPlugin)
+    .add_plugins(HudPlugin)
     .add_plugins(PausePlugin)
+    .add_plugins(SettingsPlugin)
+    .add_plugins(OptionsPlugin)
+    .add_plugins(GameOverPlugin)
+    .add_plugins(WaveBannerPlugin)
+    .add_plugins(LevelAssetsPlugin)
     ;
 
-    app
-    .add_event::<EvSpawnAsteroidFragments>()
-    .add_event::<EvSpawnBounceEffect>()
-    .add_event::<EvShieldCollision>()
-    ;
+    // Collision benchmark scene
+    if std::env::args().any(|arg| arg == "--benchmark") {
+        app.add_plugins(BenchmarkPlugin);
+    }
 
-    app.init_resource::<Textures>()
-    ;
+    // Log asset and entity counts, to check that nothing piles up
+    if std::env::args().any(|arg| arg == "--asset-stats") {
+        app.add_plugins(AssetStatsPlugin);
+    }
 
     app.run();
-}
-
-impl FromWorld for Textures {
-    fn from_world(world: &mut World) -> Self {
-        let asset_server = world.get_resource::<AssetServer>().unwrap();
-        Textures {
-            ship: asset_server.load(SHIP_SPRITE),
-            shield: asset_server.load(SHIELD_SPRITE),
-            bullet: asset_server.load(BULLET_SPRITE),
-            asteroid_1: asset_server.load(ASTEROID_1_SPRITE),
-            background: asset_server.load(BACKGROUND_SPRITE),
-            color_gradients: asset_server.load(TEXTURE_SPRITE),
-        }
-    }
 }
\ No newline at end of file
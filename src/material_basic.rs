@@ -4,6 +4,7 @@ use bevy::{
     render::render_resource::{AsBindGroup, ShaderRef},
     sprite::{Material2d, Material2dPlugin},
 };
+use crate::consts::*;
 
 pub struct MaterialBasicPlugin;
 
@@ -15,7 +16,7 @@ impl Plugin for MaterialBasicPlugin {
 
 impl Material2d for MaterialBasic {
     fn fragment_shader() -> ShaderRef {
-        "shaders/basic_texture.wgsl".into()
+        BASIC_TEXTURE_SHADER.into()
     }
 }
 
@@ -26,4 +27,6 @@ pub struct MaterialBasic {
     #[texture(0)]
     #[sampler(1)]
     pub texture: Option<Handle<Image>>,
+    #[uniform(2)]
+    pub tint: Color, // Multiplies the texture
 }
\ No newline at end of file
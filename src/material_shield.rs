@@ -7,17 +7,18 @@ use bevy::{
     sprite::{Material2d, Material2dPlugin},
 };
 
-use crate::c_appstate::AppState;
-use crate::helpers::*;
+use crate::s_simulation::simulation_running;
 use crate::consts::*;
 use crate::c_events::EvShieldCollision;
+use crate::c_playfield::Playfield;
+use crate::c_players::PlayerId;
 
 pub struct MaterialShieldPlugin;
 
 impl Plugin for MaterialShieldPlugin {
     fn build(&self, app: &mut App) {
         app.add_plugins(Material2dPlugin::<MaterialShield>::default())
-        .add_systems(Update, shield_collision.run_if(in_state(AppState::InGame)))
+        .add_systems(Update, shield_collision.run_if(simulation_running))
         ;
     }
 }
@@ -25,7 +26,7 @@ impl Plugin for MaterialShieldPlugin {
 
 impl Material2d for MaterialShield {
     fn fragment_shader() -> ShaderRef {
-        "shaders/shield.wgsl".into()
+        SHIELD_SHADER.into()
     }
 }
 
@@ -64,20 +65,26 @@ impl Default for MaterialShield {
     }
 }
 
+// Flashes the shield where it was hit. Only the shields in the color of the player holding it, as the material is shared by all of them.
 fn shield_collision (
+    playfield: Res<Playfield>,
     mut shield_collision_reader: EventReader<EvShieldCollision>,
     mut res_shield: ResMut<Assets<MaterialShield>>,
+    query_player_ids: Query<&PlayerId>,
 ) {
     for event in shield_collision_reader.read() {
         let shield_position = event.shield_position;
         let other_position = event.other_position;
-        let other_closest_position = closest_position(shield_position.x, shield_position.y, other_position.x, other_position.y);
-        let delta = Vec2::new(other_closest_position.x - shield_position.x, other_closest_position.y - shield_position.y);
+        let delta = playfield.torus().delta(shield_position, other_position);
 
         let mut collision_angle = (delta.y / delta.x).atan(); // Angle of collision
         if delta.x < 0.0 { collision_angle += PI; } // .atan() can only calculate an angle, not which direction along that angle
 
+        let shield_color = query_player_ids.get(event.ship).map(|player_id| player_id.profile().shield_color).ok();
         for (_, shield) in res_shield.iter_mut(){
+            if shield_color.is_some_and(|shield_color| shield_color != shield.color) {
+                continue;
+            }
             shield.time_since_collision = 0.0 as f32;
             shield.collision_angle = collision_angle;
         }
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use crate::material_basic::MaterialBasic;
+use crate::material_shield::MaterialShield;
+
+// Debug counter, started with the --asset-stats argument.
+// Logs how many meshes, materials and entities exist, which should stay flat however long the game runs.
+pub struct AssetStatsPlugin;
+
+impl Plugin for AssetStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(AssetStatsTimer(Timer::from_seconds(ASSET_STATS_SECONDS, TimerMode::Repeating)))
+        .add_systems(Update, log_asset_stats)
+        ;
+    }
+}
+
+const ASSET_STATS_SECONDS: f32 = 10.0;
+
+#[derive(Resource)]
+struct AssetStatsTimer(Timer);
+
+fn log_asset_stats (
+    time: Res<Time>,
+    mut timer: ResMut<AssetStatsTimer>,
+    meshes: Res<Assets<Mesh>>,
+    materials_basic: Res<Assets<MaterialBasic>>,
+    materials_shield: Res<Assets<MaterialShield>>,
+    query_entities: Query<()>,
+) {
+    if !timer.0.tick(time.delta()).just_finished() {
+        return;
+    }
+    info!(
+        "Assets: {} meshes, {} basic materials, {} shield materials - {} entities",
+        meshes.len(),
+        materials_basic.len(),
+        materials_shield.len(),
+        query_entities.iter().count(),
+    );
+}
\ No newline at end of file
@@ -0,0 +1,79 @@
+use bevy::prelude::*;
+use crate::consts::*;
+use crate::c_chargelevel::ChargeLevel;
+use crate::c_controls::{ControlFrame, ControlState};
+use crate::c_movement_and_collisions::{Angle, Radius, Velocity};
+use crate::c_playfield::Playfield;
+use crate::c_replay::ReplayPlayback;
+use crate::c_shipstats::Energy;
+use crate::c_sprites::AsteroidSize;
+use crate::c_tags::Player;
+use crate::s_simulation::SimulationSet;
+
+// Simple computer player for the headless simulation.
+// Turns towards the closest asteroid, charges shots enough to break it, and raises the shield when something is about to hit.
+pub struct AutopilotPlugin;
+
+impl Plugin for AutopilotPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(FixedUpdate, autopilot.run_if(not(resource_exists::<ReplayPlayback>())).in_set(SimulationSet::Input))
+        ;
+    }
+}
+
+const AIM_TOLERANCE: f32 = 0.1; // Radians
+const SHIELD_MARGIN: f32 = 40.0; // Distance between the edges of the ship and an asteroid that raises the shield
+
+// What the autopilot looks at on a ship, and the controls it fills in
+type PilotedShip = (&'static Transform, &'static Velocity, &'static Angle, &'static Radius, &'static ChargeLevel, &'static Energy, &'static mut ControlState);
+
+fn autopilot (
+    playfield: Res<Playfield>,
+    mut query_player: Query<PilotedShip, With<Player>>,
+    query_asteroids: Query<(&Transform, &Velocity, &Radius, &AsteroidSize)>,
+) {
+    let torus = playfield.torus();
+    for (transform, velocity, angle, radius, charge_level, energy, mut control_state) in query_player.iter_mut() {
+        let position = transform.translation.truncate();
+        let mut frame = ControlFrame::default();
+
+        let closest = query_asteroids.iter()
+        .map(|(asteroid_transform, asteroid_velocity, asteroid_radius, asteroid_size)| {
+            let delta = torus.delta(position, asteroid_transform.translation.truncate());
+            (delta, Vec2::new(asteroid_velocity.x - velocity.x, asteroid_velocity.y - velocity.y), asteroid_radius.0, *asteroid_size)
+        })
+        .min_by(|(delta_1, ..), (delta_2, ..)| delta_1.length().total_cmp(&delta_2.length()));
+
+        if let Some((delta, relative_velocity, asteroid_radius, asteroid_size)) = closest {
+            // Turn the shortest way towards the asteroid
+            let mut angle_difference = delta.y.atan2(delta.x) - angle.0;
+            angle_difference = (angle_difference + PI).rem_euclid(2.0 * PI) - PI;
+            frame.turn_left = angle_difference > AIM_TOLERANCE;
+            frame.turn_right = angle_difference < -AIM_TOLERANCE;
+
+            // A shot released now would hit the asteroid, also when it is across an edge
+            let on_target = torus.raycast(position, Vec2::from_angle(angle.0), delta.length(), position + delta, asteroid_radius).is_some();
+
+            // Hold fire until the charge breaks the asteroid, then release
+            let charge_needed = match asteroid_size {
+                AsteroidSize::Big => 2.0,
+                AsteroidSize::Medium => 1.0,
+                AsteroidSize::Small => 0.0,
+            };
+            let holding_fire = control_state.current.fire;
+            frame.fire = if holding_fire {
+                charge_level.0 < charge_needed || !on_target
+            } else {
+                on_target
+            };
+
+            let approaching = delta.dot(relative_velocity) < 0.0;
+            let gap = delta.length() - radius.0 - asteroid_radius;
+            let shield_up = control_state.current.shield;
+            frame.shield = approaching && gap < SHIELD_MARGIN && (shield_up || energy.0 > SHIELD_MIN_ENERGY);
+        }
+
+        control_state.advance(frame);
+    }
+}
\ No newline at end of file
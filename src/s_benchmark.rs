@@ -0,0 +1,90 @@
+use bevy::prelude::*;
+use crate::consts::*;
+use crate::helpers::*;
+use crate::c_appstate::AppState;
+use crate::c_bundles::AsteroidSmallBundle;
+use crate::c_gamerng::GameRng;
+use crate::c_movement_and_collisions::Velocity;
+use crate::c_playfield::Playfield;
+use crate::c_spatial_grid::{brute_force_contact_pairs, SpatialGrid};
+use crate::s_collision_detection::rebuild_spatial_grid;
+use crate::s_simulation::SimulationSet;
+
+// Benchmark scene, started with the --benchmark argument.
+// Fills the field with small asteroids and compares the broad-phase grid against checking every pair.
+pub struct BenchmarkPlugin;
+
+impl Plugin for BenchmarkPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<BenchmarkTimings>()
+        .add_systems(OnTransition { from: AppState::SpawnStart, to: AppState::WaveTransition }, spawn_benchmark_bodies)
+        .add_systems(FixedUpdate, compare_broad_phase.after(rebuild_spatial_grid).in_set(SimulationSet::Collision))
+        ;
+    }
+}
+
+#[derive(Resource, Default)]
+struct BenchmarkTimings {
+    ticks: u32,
+    grid_seconds: f32,
+    brute_force_seconds: f32,
+}
+
+fn spawn_benchmark_bodies (
+    mut commands: Commands,
+    mut game_rng: ResMut<GameRng>,
+    playfield: Res<Playfield>,
+) {
+    for _i in 0..BENCHMARK_BODY_COUNT {
+        commands.spawn(AsteroidSmallBundle::default())
+        .insert(Transform {
+            translation: Vec3::new(
+                rf32(&mut game_rng.gameplay, -playfield.width / 2.0, playfield.width / 2.0),
+                rf32(&mut game_rng.gameplay, -playfield.height / 2.0, playfield.height / 2.0),
+                AsteroidSmallBundle::default().physics_object.transform.translation.z,
+            ),
+            ..Default::default()
+        })
+        .insert(Velocity {
+            x: rf32(&mut game_rng.gameplay, -100.0, 100.0),
+            y: rf32(&mut game_rng.gameplay, -100.0, 100.0),
+        })
+        ;
+    }
+}
+
+fn compare_broad_phase (
+    spatial_grid: Res<SpatialGrid>,
+    mut timings: ResMut<BenchmarkTimings>,
+) {
+    // Time a full rebuild of the grid, not only the pair lookup
+    let grid_start = instant::Instant::now();
+    let mut grid = SpatialGrid::new(spatial_grid.playfield, COLLISION_GRID_CELL_SIZE);
+    for body in spatial_grid.bodies.iter() {
+        grid.insert(body.entity, body.position, body.radius);
+    }
+    let grid_pairs = grid.contact_pairs();
+    timings.grid_seconds += grid_start.elapsed().as_secs_f32();
+
+    let brute_force_start = instant::Instant::now();
+    let brute_force_pairs = brute_force_contact_pairs(&spatial_grid.playfield, &spatial_grid.bodies);
+    timings.brute_force_seconds += brute_force_start.elapsed().as_secs_f32();
+
+    if grid_pairs != brute_force_pairs {
+        error!("Broad-phase mismatch: grid found {} contacts, brute force found {}", grid_pairs.len(), brute_force_pairs.len());
+    }
+
+    timings.ticks += 1;
+    if timings.ticks == 60 {
+        info!(
+            "{} bodies, {} contacts - grid: {:.3} ms, brute force: {:.3} ms, speedup: {:.1}x",
+            spatial_grid.bodies.len(),
+            grid_pairs.len(),
+            1000.0 * timings.grid_seconds / 60.0,
+            1000.0 * timings.brute_force_seconds / 60.0,
+            timings.brute_force_seconds / timings.grid_seconds,
+        );
+        *timings = BenchmarkTimings::default();
+    }
+}
\ No newline at end of file
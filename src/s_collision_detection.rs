@@ -1,183 +1,83 @@
 use bevy::prelude::*;
-use crate::helpers::*;
-use crate::c_appstate::AppState;
-use crate::c_chargelevel::ChargeLevel;
-use crate::c_events::{EvSpawnAsteroidFragments, EvShieldCollision};
-use crate::c_lifetime_spawntime::SpawnTime;
-use crate::c_movement_and_collisions::{CollisionType, Mass, Radius, Velocity};
-use crate::c_sprites::AsteroidSize;
+use crate::c_events::EvCollision;
+use crate::consts::*;
+use crate::c_movement_and_collisions::{Angle, CollisionType, ConvexHull, Mass, Radius, Velocity};
+use crate::convex::{contact, rotate, Shape};
+use crate::c_playfield::Playfield;
+use crate::c_spatial_grid::SpatialGrid;
+use crate::s_simulation::SimulationSet;
 
 pub struct CollisionDetectionPlugin;
 
 impl Plugin for CollisionDetectionPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_systems(Update, collision_detection.run_if(in_state(AppState::InGame)))
+        .add_event::<EvCollision>()
+        .init_resource::<SpatialGrid>()
+        .add_systems(FixedUpdate, (rebuild_spatial_grid, collision_detection).chain().in_set(SimulationSet::Collision))
         ;
     }
 }
 
-fn collision_detection (
-    mut commands: Commands,
-    time: Res<Time>,
-    mut query: Query<(Entity, &Radius, &Transform, &mut Velocity, &Mass, &CollisionType, Option<&AsteroidSize>, Option<&ChargeLevel>, Option<&SpawnTime>)>,
-    mut spawn_asteroid_fragments_writer: EventWriter<EvSpawnAsteroidFragments>,
-    mut shield_collision_writer: EventWriter<EvShieldCollision>,
-//    mut bounce_effect_writer: EventWriter<EvSpawnBounceEffect>,
+pub fn rebuild_spatial_grid (
+    playfield: Res<Playfield>,
+    mut spatial_grid: ResMut<SpatialGrid>,
+    query: Query<(Entity, &Radius, &Transform), With<CollisionType>>,
 ) {
-    let mut iter = query.iter_combinations_mut();
+    if spatial_grid.playfield != *playfield {
+        *spatial_grid = SpatialGrid::new(*playfield, COLLISION_GRID_CELL_SIZE);
+    }
+    spatial_grid.clear();
+    for (entity, radius, transform) in query.iter() {
+        spatial_grid.insert(entity, transform.translation.truncate(), radius.0);
+    }
+}
 
-    while let Some([
-        (entity_1, radius_1, transform_1, mut velocity_1, mass_1, collision_type_1, asteroid_size_1, charge_level_1, spawn_time_1),
-        (entity_2, radius_2, transform_2, mut velocity_2, mass_2, collision_type_2, asteroid_size_2, charge_level_2, spawn_time_2)
-        ]) = iter.fetch_next()
-    {
-        let distance = shortest_distance(
-            transform_1.translation.x,
-            transform_1.translation.y,
-            transform_2.translation.x,
-            transform_2.translation.y,
-        );
-        if distance < radius_1.0 + radius_2.0 {
-            // Player vs Asteroid -> despawn Player
-            if collision_type_1.is_ship() && collision_type_2.is_asteroid() {
-                commands.entity(entity_1).despawn_recursive();
-            }
-            if collision_type_1.is_asteroid() && collision_type_2.is_ship() {
-                commands.entity(entity_2).despawn_recursive();
-            }
-            
-            // Bullet vs Asteroid -> despawn both or bounce
-            else if
-            collision_type_1.is_asteroid() && collision_type_2.is_bullet() ||
-            collision_type_1.is_bullet() && collision_type_2.is_asteroid()
-            {
-                let asteroid: Entity;
-                let asteroid_size: &AsteroidSize;
-                let asteroid_transform: &Transform;
-                let asteroid_velocity: Velocity;
-                let bullet: Entity;
-                let charge_level: &ChargeLevel;
-                if collision_type_1.is_asteroid() {
-                    asteroid = entity_1;
-                    asteroid_size = asteroid_size_1.unwrap();
-                    asteroid_transform = transform_1;
-                    asteroid_velocity = *velocity_1;
-                    bullet = entity_2;
-                    charge_level = charge_level_2.unwrap();
-                } else {
-                    asteroid = entity_2;
-                    asteroid_size = asteroid_size_2.unwrap();
-                    asteroid_transform = transform_2;
-                    asteroid_velocity = *velocity_2;
-                    bullet = entity_1;
-                    charge_level = charge_level_1.unwrap();
-                }
-                if asteroid_size.is_big() && charge_level.0 >= 2.0 ||
-                asteroid_size.is_medium() && charge_level.0 >= 1.0 ||
-                asteroid_size.is_small() {
-                    commands.entity(bullet).despawn_recursive();
-                    commands.entity(asteroid).despawn_recursive();
-                    spawn_asteroid_fragments_writer.send(EvSpawnAsteroidFragments{transform: *asteroid_transform, velocity: asteroid_velocity, asteroid_size_destroyed: *asteroid_size});
-                } else {
-                    collision_bounce(
-                        &mut commands,
-                        transform_1.translation,
-                        &mut velocity_1,
-                        mass_1.0,
-                        transform_2.translation,
-                        &mut velocity_2,
-                        mass_2.0,
-                        &time,
-                        radius_1.0,
-                    );
-                }
-            }
+// The shape, place and motion of a body, for finding its contacts and their impulse
+type CollidingBody = (&'static Radius, &'static Transform, &'static Velocity, &'static Mass, Option<&'static ConvexHull>, Option<&'static Angle>);
 
-            // Asteroid vs Asteroid -> Bounce
-            else if
-            collision_type_1.is_asteroid() && collision_type_2.is_asteroid()
-            {
-                collision_bounce(
-                    &mut commands,
-                    transform_1.translation,
-                    &mut velocity_1,
-                    mass_1.0,
-                    transform_2.translation,
-                    &mut velocity_2,
-                    mass_2.0,
-                    &time,
-                    radius_1.0,
-                );
-            }
+// Only finds contacts and publishes them. Responding to them is up to the subscribers of EvCollision.
+pub fn collision_detection (
+    playfield: Res<Playfield>,
+    spatial_grid: Res<SpatialGrid>,
+    query: Query<CollidingBody>,
+    mut collision_writer: EventWriter<EvCollision>,
+) {
+    // Only pairs found touching by the broad-phase grid are checked, instead of every combination.
+    // Their bounding circles overlap, the narrow phase checks whether their actual shapes do.
+    for (entity_a, entity_b) in spatial_grid.contact_pairs() {
+        let Ok([
+            (radius_a, transform_a, velocity_a, mass_a, hull_a, angle_a),
+            (radius_b, transform_b, velocity_b, mass_b, hull_b, angle_b),
+            ]) = query.get_many([entity_a, entity_b]) else { continue; };
 
-            // Shield vs anything -> Bounce
-            else if
-            collision_type_1.is_shield() && collision_type_2.is_asteroid() ||
-            collision_type_1.is_asteroid() && collision_type_2.is_shield() ||
-            collision_type_1.is_shield() && collision_type_2.is_bullet() ||
-            collision_type_1.is_bullet() && collision_type_2.is_shield()
-            {
-                collision_bounce(
-                    &mut commands,
-                    transform_1.translation,
-                    &mut velocity_1,
-                    mass_1.0,
-                    transform_2.translation,
-                    &mut velocity_2,
-                    mass_2.0,
-                    &time,
-                    radius_1.0,
-                );
+        let Some(contact) = contact(
+            &playfield.torus(),
+            transform_a.translation.truncate(), &shape(radius_a, hull_a, angle_a),
+            transform_b.translation.truncate(), &shape(radius_b, hull_b, angle_b),
+        ) else { continue; };
+        let normal = contact.normal; // Points from A to B
+        let relative_velocity = Vec2::new(velocity_b.x - velocity_a.x, velocity_b.y - velocity_a.y);
 
-                let shield_transform: &Transform;
-                let other_transform: &Transform;
-                if collision_type_1.is_shield() {
-                    shield_transform = transform_1;
-                    other_transform = transform_2;
-                } else {
-                    shield_transform = transform_2;
-                    other_transform = transform_1;
-                }
-                shield_collision_writer.send(EvShieldCollision{
-                    shield_position: Vec2::new(shield_transform.translation.x, shield_transform.translation.y),
-                    other_position: Vec2::new(other_transform.translation.x, other_transform.translation.y)
-                });
-            }
+        // Impulse on B of a perfectly elastic collision, zero if the bodies are already moving apart
+        let approach_speed = (-relative_velocity.dot(normal)).max(0.0);
+        let impulse = normal * 2.0 * mass_a.0 * mass_b.0 / (mass_a.0 + mass_b.0) * approach_speed;
+
+        collision_writer.send(EvCollision {
+            a: entity_a,
+            b: entity_b,
+            normal,
+            contact_point: contact.point,
+            penetration: contact.penetration,
+            impulse,
+        });
+    }
+}
 
-            // Bullet vs Ship -> Despawn both or bounce
-            else if
-            collision_type_1.is_ship() && collision_type_2.is_bullet() ||
-            collision_type_1.is_bullet() && collision_type_2.is_ship()
-            {
-                let bullet_charge: &ChargeLevel;
-                let bullet_spawn_time: &SpawnTime;
-                if collision_type_1.is_bullet() {
-                    bullet_charge = charge_level_1.unwrap();
-                    bullet_spawn_time = spawn_time_1.unwrap();
-                } else {
-                    bullet_charge = charge_level_2.unwrap();
-                    bullet_spawn_time = spawn_time_2.unwrap();
-                }
-                if bullet_spawn_time.0.elapsed().as_secs_f32() > 0.2 {
-                    if bullet_charge.0 > 1.0 {
-                        commands.entity(entity_1).despawn_recursive();
-                        commands.entity(entity_2).despawn_recursive();
-                    } else {
-                        collision_bounce(
-                            &mut commands,
-                            transform_1.translation,
-                            &mut velocity_1,
-                            mass_1.0,
-                            transform_2.translation,
-                            &mut velocity_2,
-                            mass_2.0,
-                            &time,
-                            radius_1.0,
-                        );
-                    }
-                }
-            }
-        }
+// Bodies without a hull collide as circles
+fn shape(radius: &Radius, hull: Option<&ConvexHull>, angle: Option<&Angle>) -> Shape {
+    match hull {
+        Some(hull) => Shape::Polygon(rotate(&hull.0, angle.map_or(0.0, |angle| angle.0))),
+        None => Shape::Circle(radius.0),
     }
 }
\ No newline at end of file
@@ -0,0 +1,200 @@
+use bevy::prelude::*;
+use crate::helpers::*;
+use crate::c_chargelevel::ChargeLevel;
+use crate::c_collision_rules::{CollisionBody, CollisionResponse, CollisionRuleEvent, CollisionRules, Side};
+use crate::c_events::{EvBounce, EvCollision, EvDamage, EvDestroy, EvKill, EvShieldCollision, EvSpawnAsteroidFragments, EvSpawnBounceEffect};
+use crate::c_lifetime_spawntime::{SimTick, SpawnTime};
+use crate::c_movement_and_collisions::{Angle, AngularVelocity, CollisionType, ConvexHull, Friction, Mass, Radius, Restitution, Velocity};
+use crate::c_playfield::Playfield;
+use crate::c_shipstats::Energy;
+use crate::c_sprites::AsteroidSize;
+use crate::s_simulation::SimulationSet;
+
+pub struct CollisionResponsePlugin;
+
+impl Plugin for CollisionResponsePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(CollisionRules::load())
+        .add_systems(FixedUpdate, (
+            apply_collision_rules,
+            collision_bounce_response,
+            destroy_response,
+            damage_response,
+        ).chain().in_set(SimulationSet::Response))
+        ;
+    }
+}
+
+// What the collision rules and their responses look at on each body
+type RuleBody = (&'static Transform, &'static Velocity, &'static CollisionType, Option<&'static AsteroidSize>, Option<&'static ChargeLevel>, Option<&'static SpawnTime>);
+
+// Looks up the rule for every collision and sends out the events for the responses of the matching outcome
+#[allow(clippy::too_many_arguments)]
+pub fn apply_collision_rules (
+    collision_rules: Res<CollisionRules>,
+    sim_tick: Res<SimTick>,
+    query: Query<RuleBody>,
+    query_outlines: Query<(&ConvexHull, &Angle)>,
+    mut collision_reader: EventReader<EvCollision>,
+    mut bounce_writer: EventWriter<EvBounce>,
+    mut destroy_writer: EventWriter<EvDestroy>,
+    mut damage_writer: EventWriter<EvDamage>,
+    mut spawn_asteroid_fragments_writer: EventWriter<EvSpawnAsteroidFragments>,
+    mut shield_collision_writer: EventWriter<EvShieldCollision>,
+    mut kill_writer: EventWriter<EvKill>,
+) {
+    for collision in collision_reader.read() {
+        let Ok([
+            (transform_1, velocity_1, collision_type_1, asteroid_size_1, charge_level_1, spawn_time_1),
+            (transform_2, velocity_2, collision_type_2, asteroid_size_2, charge_level_2, spawn_time_2)
+            ]) = query.get_many([collision.a, collision.b]) else { continue; };
+
+        let Some((outcomes, reversed)) = collision_rules.get(*collision_type_1, *collision_type_2) else { continue; };
+
+        // Side A of the rule is entity 1, unless the pair matched the rule in reverse order
+        let body_1 = CollisionBody { asteroid_size: asteroid_size_1, charge_level: charge_level_1, spawn_time: spawn_time_1 };
+        let body_2 = CollisionBody { asteroid_size: asteroid_size_2, charge_level: charge_level_2, spawn_time: spawn_time_2 };
+        let (body_a, body_b) = if reversed { (&body_2, &body_1) } else { (&body_1, &body_2) };
+        let is_entity_1 = |side: &Side| (*side == Side::A) != reversed;
+
+        let Some(outcome) = outcomes.iter().find(|outcome| outcome.applies(body_a, body_b, &sim_tick)) else { continue; };
+
+        for response in outcome.then.iter() {
+            match response {
+                CollisionResponse::Ignore => {}
+                CollisionResponse::Bounce => {
+                    bounce_writer.send(EvBounce{ collision: *collision });
+                }
+                CollisionResponse::Destroy(side) => {
+                    let entity = if is_entity_1(side) { collision.a } else { collision.b };
+                    destroy_writer.send(EvDestroy{ entity });
+                }
+                CollisionResponse::Damage(side, amount) => {
+                    let entity = if is_entity_1(side) { collision.a } else { collision.b };
+                    damage_writer.send(EvDamage{ entity, amount: *amount });
+                }
+                CollisionResponse::Emit(CollisionRuleEvent::AsteroidFragments(side)) => {
+                    let (asteroid, asteroid_size, asteroid_transform, asteroid_velocity) = if is_entity_1(side) {
+                        (collision.a, asteroid_size_1, transform_1, velocity_1)
+                    } else {
+                        (collision.b, asteroid_size_2, transform_2, velocity_2)
+                    };
+                    if let Some(asteroid_size) = asteroid_size {
+                        let outline = query_outlines.get(asteroid).ok();
+                        spawn_asteroid_fragments_writer.send(EvSpawnAsteroidFragments{
+                            transform: *asteroid_transform,
+                            velocity: *asteroid_velocity,
+                            asteroid_size_destroyed: *asteroid_size,
+                            hull: outline.map(|(hull, _)| hull.clone()),
+                            angle: outline.map_or(0.0, |(_, angle)| angle.0),
+                        });
+                    }
+                }
+                CollisionResponse::Emit(CollisionRuleEvent::ShieldCollision(side)) => {
+                    let (ship, shield_transform, other_transform) = if is_entity_1(side) {
+                        (collision.a, transform_1, transform_2)
+                    } else {
+                        (collision.b, transform_2, transform_1)
+                    };
+                    shield_collision_writer.send(EvShieldCollision{
+                        ship,
+                        shield_position: Vec2::new(shield_transform.translation.x, shield_transform.translation.y),
+                        other_position: Vec2::new(other_transform.translation.x, other_transform.translation.y)
+                    });
+                }
+                CollisionResponse::Emit(CollisionRuleEvent::Kill(side)) => {
+                    let (bullet, ship) = if is_entity_1(side) { (collision.a, collision.b) } else { (collision.b, collision.a) };
+                    kill_writer.send(EvKill{ bullet, ship });
+                }
+            }
+        }
+    }
+}
+
+// What a bounce moves and turns on each body, and what it needs to know to do so
+type BouncingBody = (&'static mut Transform, &'static mut Velocity, Option<&'static mut AngularVelocity>, &'static Mass, &'static Radius, &'static Restitution, &'static Friction, Has<ConvexHull>);
+
+pub fn collision_bounce_response (
+    playfield: Res<Playfield>,
+    mut query: Query<BouncingBody>,
+    mut bounce_reader: EventReader<EvBounce>,
+    mut bounce_effect_writer: EventWriter<EvSpawnBounceEffect>,
+) {
+    for bounce in bounce_reader.read() {
+        let Ok([
+            (mut transform_1, mut velocity_1, mut angular_velocity_1, mass_1, radius_1, restitution_1, friction_1, has_hull_1),
+            (mut transform_2, mut velocity_2, mut angular_velocity_2, mass_2, radius_2, restitution_2, friction_2, has_hull_2)
+            ]) = query.get_many_mut([bounce.collision.a, bounce.collision.b]) else { continue; };
+
+        let mut body_1 = BounceBody {
+            position: transform_1.translation.truncate(),
+            velocity: Vec2::new(velocity_1.x, velocity_1.y),
+            mass: mass_1.0,
+            radius: radius_1.0,
+            restitution: restitution_1.0,
+            friction: friction_1.0,
+            angular_velocity: angular_velocity_1.as_ref().map_or(0.0, |angular_velocity| angular_velocity.0),
+            inverse_inertia: angular_velocity_1.as_ref().map_or(0.0, |_| BounceBody::disc_inverse_inertia(mass_1.0, radius_1.0)),
+        };
+        let mut body_2 = BounceBody {
+            position: transform_2.translation.truncate(),
+            velocity: Vec2::new(velocity_2.x, velocity_2.y),
+            mass: mass_2.0,
+            radius: radius_2.0,
+            restitution: restitution_2.0,
+            friction: friction_2.0,
+            angular_velocity: angular_velocity_2.as_ref().map_or(0.0, |angular_velocity| angular_velocity.0),
+            inverse_inertia: angular_velocity_2.as_ref().map_or(0.0, |_| BounceBody::disc_inverse_inertia(mass_2.0, radius_2.0)),
+        };
+        // Circles are measured again, as an earlier bounce this tick may have moved them.
+        // The contact of hulls comes from the narrow phase, their radius is only a bound.
+        let impulse = if has_hull_1 || has_hull_2 {
+            collision_bounce_along(&playfield.torus(), &mut body_1, &mut body_2, bounce.collision.normal, bounce.collision.penetration)
+        } else {
+            collision_bounce(&playfield.torus(), &mut body_1, &mut body_2)
+        };
+
+        transform_1.translation.x = body_1.position.x;
+        transform_1.translation.y = body_1.position.y;
+        transform_2.translation.x = body_2.position.x;
+        transform_2.translation.y = body_2.position.y;
+        *velocity_1 = Velocity { x: body_1.velocity.x, y: body_1.velocity.y };
+        *velocity_2 = Velocity { x: body_2.velocity.x, y: body_2.velocity.y };
+        if let Some(angular_velocity) = angular_velocity_1.as_mut() {
+            angular_velocity.0 = body_1.angular_velocity;
+        }
+        if let Some(angular_velocity) = angular_velocity_2.as_mut() {
+            angular_velocity.0 = body_2.angular_velocity;
+        }
+
+        if let Some(impulse) = impulse {
+            bounce_effect_writer.send(EvSpawnBounceEffect{
+                contact_point: bounce.collision.contact_point,
+                change_of_momentum: impulse.length(),
+            });
+        }
+    }
+}
+
+pub fn destroy_response (
+    mut commands: Commands,
+    mut destroy_reader: EventReader<EvDestroy>,
+) {
+    for destroy in destroy_reader.read() {
+        if let Some(entity_commands) = commands.get_entity(destroy.entity) {
+            entity_commands.despawn_recursive();
+        }
+    }
+}
+
+fn damage_response (
+    mut query: Query<&mut Energy>,
+    mut damage_reader: EventReader<EvDamage>,
+) {
+    for damage in damage_reader.read() {
+        if let Ok(mut energy) = query.get_mut(damage.entity) {
+            energy.0 = (energy.0 - damage.amount).max(0.0);
+        }
+    }
+}
\ No newline at end of file
@@ -1,41 +1,77 @@
 use bevy::prelude::*;
-use crate::c_appstate::AppState;
+use crate::consts::*;
 use crate::c_bundles::{BulletBundle, ShieldBundle};
 use crate::c_movement_and_collisions::{Angle, CollisionType, Mass, Velocity};
 use crate::c_tags::{Player, Shield};
 use crate::c_chargelevel::ChargeLevel;
-use crate::c_shipstats::{Energy, ShipStats};
+use crate::c_shipstats::{Energy, Invulnerable, ShipStats};
+use crate::c_controls::ControlState;
+use crate::c_lifetime_spawntime::{SimTick, SpawnTime};
+use crate::c_playfield::Playfield;
+use crate::c_players::{FiredBy, PlayerId};
+use crate::c_replay::ReplayPlayback;
+use crate::c_settings::Settings;
+use crate::s_simulation::SimulationSet;
 
 pub struct ControlPlugin;
 
 impl Plugin for ControlPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_systems(Update, control.run_if(in_state(AppState::InGame)))
+        .init_resource::<Settings>()
+        .add_systems(FixedUpdate, read_controls
+            .run_if(resource_exists::<Input<KeyCode>>().and_then(not(resource_exists::<ReplayPlayback>())))
+            .in_set(SimulationSet::Input))
+        .add_systems(FixedUpdate, control.in_set(SimulationSet::Control))
         ;
     }
 }
 
+// Samples the keyboard and gamepads once per tick, through the bindings of each player.
+// Without input devices, like in the headless simulation, something else has to fill in the ControlState.
+pub fn read_controls(
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    gamepad_axes: Res<Axis<GamepadAxis>>,
+    settings: Res<Settings>,
+    mut query: Query<(&PlayerId, &mut ControlState), With<Player>>,
+) {
+    for (player_id, mut control_state) in query.iter_mut() {
+        let Some(controls) = settings.controls.get(player_id.0) else { continue; };
+        control_state.advance(controls.frame(&keyboard_input, &gamepad_buttons, &gamepad_axes));
+    }
+}
+
 fn control(
     mut commands: Commands,
     time: Res<Time>,
-    keyboard_input: Res<Input<KeyCode>>,
+    sim_tick: Res<SimTick>,
+    playfield: Res<Playfield>,
     mut query: Query<(
         Entity,
         &mut Velocity,
         &ShipStats,
+        &ControlState,
         &mut Angle,
         &Transform,
         &Energy,
         &mut ChargeLevel,
+        &PlayerId,
+        Option<&Invulnerable>,
+        Option<&Children>,
         With<Player>,
     )>,
-    mut query_shield: Query<(Entity, With<Shield>)>,
+    query_shield: Query<Entity, With<Shield>>,
 ) {
-    for (entity, mut velocity, ship_stats, mut angle, transform, energy, mut charge_level, _) in query.iter_mut() {
+    for (entity, mut velocity, ship_stats, control_state, mut angle, transform, energy, mut charge_level, player_id, invulnerable, children, _) in query.iter_mut() {
+        let current = &control_state.current;
+        let previous = &control_state.previous;
+
+        // The shield of a respawned ship stays up until its invulnerability ends
+        let shield_locked = invulnerable.is_some();
 
         // Activate Shield
-        if keyboard_input.just_pressed(ship_stats.controls.shield) && energy.0 > 20. {
+        if current.shield && !previous.shield && energy.0 > SHIELD_MIN_ENERGY && !shield_locked {
             let shield_entity = commands
             .spawn(ShieldBundle {
                 ..Default::default()
@@ -45,43 +81,45 @@ fn control(
             commands.entity(entity).push_children(&[shield_entity])
             .insert(CollisionType::Shield);
         }
-        // Deactivate Shield
-        if keyboard_input.just_released(KeyCode::Z) {
+        // Deactivate Shield, only the one of this ship
+        if !current.shield && previous.shield && !shield_locked {
             commands.entity(entity).insert(CollisionType::Ship);
-            for (shield_entity, _) in query_shield.iter_mut() {
+            for shield_entity in query_shield.iter_many(children.into_iter().flatten()) {
                 commands.entity(shield_entity).despawn_recursive();
             }
         }
 
         // Rotation
-        if keyboard_input.pressed(ship_stats.controls.turn_left) {
+        if current.turn_left {
             angle.0 += ship_stats.turn_rate * time.delta_seconds();
         }
-        if keyboard_input.pressed(ship_stats.controls.turn_right) {
+        if current.turn_right {
             angle.0 -= ship_stats.turn_rate * time.delta_seconds();
         }
+        // Steering with a stick turns the short way round, and stops when facing the stick
+        if let Some(steer) = current.steer {
+            let max_turn = ship_stats.turn_rate * time.delta_seconds();
+            angle.0 += Vec2::from_angle(angle.0).angle_between(Vec2::from_angle(steer)).clamp(-max_turn, max_turn);
+        }
 
         // Acceleration
-        if keyboard_input.pressed(ship_stats.controls.accelerate) {
+        if current.accelerate {
             velocity.x += ship_stats.acceleration * angle.0.cos() * time.delta_seconds();
             velocity.y += ship_stats.acceleration * angle.0.sin() * time.delta_seconds();
         }
 
         // Fire
-        if keyboard_input.pressed(ship_stats.controls.fire) {
+        if current.fire {
             charge_level.0 += ship_stats.charge_rate * time.delta_seconds();
-            if charge_level.0 > 2.0 {charge_level.0 = 2.0;}
+            if charge_level.0 > MAX_CHARGE {charge_level.0 = MAX_CHARGE;}
         }
-        if keyboard_input.just_released(ship_stats.controls.fire) {
+        if !current.fire && previous.fire {
+            let bullet_position = playfield.torus().wrap(transform.translation.truncate() + Vec2::from_angle(angle.0) * 25.0);
             commands.spawn(BulletBundle {
                 ..Default::default()
             })
             .insert(Transform {
-                translation: Vec3::new(
-                    transform.translation.x + angle.0.cos() * 25.0,
-                    transform.translation.y + angle.0.sin() * 25.0,
-                    10.0,
-                ),
+                translation: bullet_position.extend(10.0),
                 ..Default::default()
             })
             .insert(Angle(angle.0))
@@ -90,7 +128,9 @@ fn control(
                 y: velocity.y + angle.0.sin() * ship_stats.bullet_speed,
             })
             .insert(ChargeLevel(charge_level.0))
+            .insert(SpawnTime(sim_tick.0))
             .insert(Mass(1.0 + charge_level.0))
+            .insert(FiredBy(*player_id))
             ;
             charge_level.0 = ChargeLevel::default().0;
         }
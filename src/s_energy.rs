@@ -1,25 +1,46 @@
 use bevy::prelude::*;
-use crate::c_appstate::AppState;
-use crate::c_shipstats::{ShipStats, Energy};
+use crate::consts::*;
+use crate::c_shipstats::{ShipStats, Energy, Invulnerable};
+use crate::c_controls::ControlState;
+use crate::c_lifetime_spawntime::SimTick;
 use crate::c_tags::Shield;
 use crate::c_movement_and_collisions::CollisionType;
+use crate::s_simulation::SimulationSet;
 
 pub struct EnergyPlugin;
 
 impl Plugin for EnergyPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_systems(Update, gain_energy.run_if(in_state(AppState::InGame)))
-        .add_systems(Update, drain_energy.run_if(in_state(AppState::InGame)))
+        .add_systems(FixedUpdate, (end_invulnerability, gain_energy, drain_energy).chain().in_set(SimulationSet::Energy))
         ;
     }
 }
 
+// Drops the shield of a respawned ship, unless the player is holding it up by then
+fn end_invulnerability(
+    mut commands: Commands,
+    sim_tick: Res<SimTick>,
+    query: Query<(Entity, &Invulnerable, &ControlState, &Children)>,
+    query_shield: Query<Entity, With<Shield>>,
+) {
+    for (entity, invulnerable, control_state, children) in query.iter() {
+        if sim_tick.0 < invulnerable.0 { continue; }
+        commands.entity(entity).remove::<Invulnerable>();
+        if !control_state.current.shield {
+            commands.entity(entity).insert(CollisionType::Ship);
+            for shield_entity in query_shield.iter_many(children) {
+                commands.entity(shield_entity).despawn_recursive();
+            }
+        }
+    }
+}
+
 fn gain_energy(time: Res<Time>, mut query: Query<(&ShipStats, &mut Energy)>) {
     for (ship_stats, mut energy) in query.iter_mut() {
         energy.0 += ship_stats.shield_regeneration * time.delta_seconds();
-        if energy.0 >= 100. {
-            energy.0 = 100.;
+        if energy.0 >= MAX_ENERGY {
+            energy.0 = MAX_ENERGY;
         }
     }
 }
@@ -27,12 +48,12 @@ fn gain_energy(time: Res<Time>, mut query: Query<(&ShipStats, &mut Energy)>) {
 fn drain_energy(
     mut commands: Commands,
     time: Res<Time>,
-    mut query: Query<(Entity, &mut Energy, &CollisionType, &Children)>,
+    mut query: Query<(Entity, &mut Energy, &CollisionType, &Children), Without<Invulnerable>>,
     mut query_shield: Query<(Entity, With<Shield>)>,
 ) {
     for (entity, mut energy, collision_type, children) in query.iter_mut(){
         if collision_type.is_shield() {
-            energy.0 -= 100. * time.delta_seconds();
+            energy.0 -= SHIELD_ENERGY_DRAIN * time.delta_seconds();
             if energy.0 <= 0. {
                 commands.entity(entity).insert(CollisionType::Ship);
                 for child in children.into_iter() {
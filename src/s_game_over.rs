@@ -0,0 +1,72 @@
+use bevy::prelude::*;
+use crate::consts::*;
+use crate::c_appstate::AppState;
+use crate::c_players::LocalPlayers;
+use crate::c_score::{Kills, Score};
+use crate::c_tags::GameOverText;
+
+// Shows the final score when the last ship is lost, then goes back to the title screen
+pub struct GameOverPlugin;
+
+impl Plugin for GameOverPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(OnEnter(AppState::GameOver), spawn_game_over_text)
+        .add_systems(Update, game_over_to_main_menu.run_if(in_state(AppState::GameOver)))
+        .add_systems(OnExit(AppState::GameOver), despawn_with::<GameOverText>)
+        ;
+    }
+}
+
+#[derive(Resource)]
+struct GameOverTimer(Timer);
+
+pub fn centered_text(text: String, font_size: f32) -> Text2dBundle {
+    Text2dBundle {
+        text: Text::from_section(text, TextStyle {
+            font_size,
+            color: Color::WHITE,
+            ..Default::default()
+        })
+        .with_alignment(TextAlignment::Center),
+        transform: Transform::from_xyz(0.0, 0.0, 200.0),
+        ..Default::default()
+    }
+}
+
+fn spawn_game_over_text (
+    mut commands: Commands,
+    score: Res<Score>,
+    kills: Res<Kills>,
+    local_players: Res<LocalPlayers>,
+) {
+    let mut text = format!("GAME OVER\nScore: {}", score.0);
+    // With more players, also who shot down whom the most
+    if local_players.0 > 1 {
+        for (player, player_kills) in kills.0.iter().take(local_players.0).enumerate() {
+            text.push_str(&format!("\nPlayer {} kills: {}", player + 1, player_kills));
+        }
+    }
+    commands.spawn(centered_text(text, 64.0))
+    .insert(GameOverText);
+    commands.insert_resource(GameOverTimer(Timer::from_seconds(GAME_OVER_SECONDS, TimerMode::Once)));
+}
+
+fn game_over_to_main_menu (
+    time: Res<Time>,
+    mut timer: ResMut<GameOverTimer>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    if timer.0.tick(time.delta()).finished() {
+        next_state.set(AppState::MainMenu);
+    }
+}
+
+pub fn despawn_with<T: Component> (
+    mut commands: Commands,
+    query: Query<Entity, With<T>>,
+) {
+    for entity in query.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+}
\ No newline at end of file
@@ -0,0 +1,45 @@
+use bevy::prelude::*;
+use crate::c_appstate::AppState;
+use crate::c_events::*;
+use crate::c_playfield::Playfield;
+use crate::c_players::LocalPlayers;
+use crate::s_collision_detection::CollisionDetectionPlugin;
+use crate::s_collision_response::CollisionResponsePlugin;
+use crate::s_control::ControlPlugin;
+use crate::s_energy::EnergyPlugin;
+use crate::s_movement::MovementPlugin;
+use crate::s_score::ScorePlugin;
+use crate::s_waves::WavePlugin;
+use crate::s_simulation::SimulationPlugin;
+use crate::s_spawn_despawn::SpawnDespawnPlugin;
+
+// The whole simulation, without anything that needs a window, rendering or assets.
+// Shared by the game, the headless simulation and the tests.
+pub struct GameplayPlugin;
+
+impl Plugin for GameplayPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_state::<AppState>()
+        .init_resource::<Playfield>()
+        .add_event::<EvSpawnAsteroidFragments>()
+        .add_event::<EvSpawnBounceEffect>()
+        .add_event::<EvShieldCollision>()
+        .add_event::<EvKill>()
+        .init_resource::<LocalPlayers>()
+        .add_event::<EvDestroy>()
+        .add_event::<EvDamage>()
+        .add_event::<EvBounce>()
+        .add_event::<EvRestartWave>()
+        .add_plugins(SimulationPlugin)
+        .add_plugins(EnergyPlugin)
+        .add_plugins(MovementPlugin)
+        .add_plugins(SpawnDespawnPlugin)
+        .add_plugins(CollisionDetectionPlugin)
+        .add_plugins(CollisionResponsePlugin)
+        .add_plugins(ControlPlugin)
+        .add_plugins(ScorePlugin)
+        .add_plugins(WavePlugin)
+        ;
+    }
+}
\ No newline at end of file
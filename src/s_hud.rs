@@ -0,0 +1,182 @@
+use bevy::prelude::*;
+use crate::consts::*;
+use crate::c_appstate::AppState;
+use crate::c_chargelevel::ChargeLevel;
+use crate::c_collision_rules::CollisionRules;
+use crate::c_hud::{HudChargeFill, HudEnergyFill, HudStatusText};
+use crate::c_levels::Wave;
+use crate::c_players::{LocalPlayers, PlayerId};
+use crate::c_score::{Lives, Score};
+use crate::c_shipstats::Energy;
+use crate::c_tags::{HudRoot, Player};
+use crate::s_game_over::despawn_with;
+
+// Energy bar and charge meter of each player along the top, with score, lives and wave on the right.
+// Everything is only touched when the components or resources it shows change.
+pub struct HudPlugin;
+
+impl Plugin for HudPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(OnEnter(AppState::SpawnStart), (despawn_with::<HudRoot>, spawn_hud).chain())
+        .add_systems(OnEnter(AppState::MainMenu), despawn_with::<HudRoot>)
+        .add_systems(Update, (update_energy_bars, update_charge_meters, update_status_text))
+        ;
+    }
+}
+
+const BAR_WIDTH: f32 = 160.0;
+const BAR_BACKGROUND_COLOR: Color = Color::rgba(1.0, 1.0, 1.0, 0.15);
+const ENERGY_COLOR: Color = Color::rgb(0.3, 0.6, 1.0);
+const ENERGY_LOW_COLOR: Color = Color::rgb(0.8, 0.25, 0.2); // Too little to raise the shield
+const CHARGE_COLOR: Color = Color::rgb(1.0, 0.8, 0.3);
+const MARK_COLOR: Color = Color::WHITE;
+
+fn status_text(score: &Score, lives: &Lives, wave: &Wave) -> String {
+    format!("Score {}   Lives {}   Wave {}", score.0, lives.remaining, wave.number)
+}
+
+fn energy_color(energy: f32) -> Color {
+    if energy > SHIELD_MIN_ENERGY { ENERGY_COLOR } else { ENERGY_LOW_COLOR }
+}
+
+// A bar with a filled part that grows from the left, and thin marks at the given fractions of its length
+fn spawn_bar(parent: &mut ChildBuilder, height: f32, fill: impl Bundle, fill_color: Color, marks: &[f32]) {
+    parent.spawn(NodeBundle {
+        style: Style {
+            width: Val::Px(BAR_WIDTH),
+            height: Val::Px(height),
+            ..Default::default()
+        },
+        background_color: BAR_BACKGROUND_COLOR.into(),
+        ..Default::default()
+    })
+    .with_children(|bar| {
+        bar.spawn(NodeBundle {
+            style: Style {
+                width: Val::Percent(100.0),
+                height: Val::Percent(100.0),
+                ..Default::default()
+            },
+            background_color: fill_color.into(),
+            ..Default::default()
+        })
+        .insert(fill);
+        for mark in marks {
+            bar.spawn(NodeBundle {
+                style: Style {
+                    position_type: PositionType::Absolute,
+                    left: Val::Px((BAR_WIDTH * mark - 1.0).min(BAR_WIDTH - 2.0)),
+                    width: Val::Px(2.0),
+                    height: Val::Percent(100.0),
+                    ..Default::default()
+                },
+                background_color: MARK_COLOR.into(),
+                ..Default::default()
+            });
+        }
+    });
+}
+
+fn spawn_hud (
+    mut commands: Commands,
+    local_players: Res<LocalPlayers>,
+    collision_rules: Res<CollisionRules>,
+    score: Res<Score>,
+    lives: Res<Lives>,
+    wave: Res<Wave>,
+) {
+    // A mark at every charge that makes a difference to a collision
+    let charge_marks: Vec<f32> = collision_rules.charge_thresholds().iter()
+        .filter(|threshold| **threshold > 0.0 && **threshold <= MAX_CHARGE)
+        .map(|threshold| threshold / MAX_CHARGE)
+        .collect();
+    commands.spawn(NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            justify_content: JustifyContent::SpaceBetween,
+            padding: UiRect::all(Val::Px(10.0)),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .insert(HudRoot)
+    .with_children(|hud| {
+        hud.spawn(NodeBundle {
+            style: Style {
+                column_gap: Val::Px(24.0),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .with_children(|players| {
+            for player_id in (0..local_players.0).map(PlayerId) {
+                players.spawn(NodeBundle {
+                    style: Style {
+                        flex_direction: FlexDirection::Column,
+                        row_gap: Val::Px(4.0),
+                        ..Default::default()
+                    },
+                    ..Default::default()
+                })
+                .with_children(|panel| {
+                    panel.spawn(TextBundle::from_section(format!("P{}", player_id.0 + 1), TextStyle {
+                        font_size: 20.0,
+                        color: player_id.profile().tint,
+                        ..Default::default()
+                    }));
+                    spawn_bar(panel, 10.0, HudEnergyFill(player_id), ENERGY_COLOR, &[SHIELD_MIN_ENERGY / MAX_ENERGY]);
+                    spawn_bar(panel, 6.0, HudChargeFill(player_id), CHARGE_COLOR, &charge_marks);
+                });
+            }
+        });
+        hud.spawn(TextBundle::from_section(status_text(&score, &lives, &wave), TextStyle {
+            font_size: 24.0,
+            color: Color::WHITE,
+            ..Default::default()
+        }))
+        .insert(HudStatusText);
+    });
+}
+
+// Ships whose bars are out of date, leaving the HUD alone on the frames nothing changed
+type ChangedShip<T> = (With<Player>, Changed<T>);
+
+fn update_energy_bars (
+    query_ships: Query<(&PlayerId, &Energy), ChangedShip<Energy>>,
+    mut query_fills: Query<(&HudEnergyFill, &mut Style, &mut BackgroundColor)>,
+) {
+    for (player_id, energy) in query_ships.iter() {
+        for (fill, mut style, mut background_color) in query_fills.iter_mut() {
+            if fill.0 == *player_id {
+                style.width = Val::Percent(100.0 * (energy.0 / MAX_ENERGY).clamp(0.0, 1.0));
+                background_color.0 = energy_color(energy.0);
+            }
+        }
+    }
+}
+
+fn update_charge_meters (
+    query_ships: Query<(&PlayerId, &ChargeLevel), ChangedShip<ChargeLevel>>,
+    mut query_fills: Query<(&HudChargeFill, &mut Style)>,
+) {
+    for (player_id, charge_level) in query_ships.iter() {
+        for (fill, mut style) in query_fills.iter_mut() {
+            if fill.0 == *player_id {
+                style.width = Val::Percent(100.0 * (charge_level.0 / MAX_CHARGE).clamp(0.0, 1.0));
+            }
+        }
+    }
+}
+
+fn update_status_text (
+    score: Res<Score>,
+    lives: Res<Lives>,
+    wave: Res<Wave>,
+    mut query: Query<&mut Text, With<HudStatusText>>,
+) {
+    if !score.is_changed() && !lives.is_changed() && !wave.is_changed() { return; }
+    for mut text in query.iter_mut() {
+        text.sections[0].value = status_text(&score, &lives, &wave);
+    }
+}
\ No newline at end of file
@@ -0,0 +1,108 @@
+use bevy::prelude::*;
+use crate::consts::*;
+use crate::c_appstate::AppState;
+use crate::c_events::EvRestartWave;
+use crate::c_levels::{LevelDefinition, LevelList, LevelListLoader, LevelLoader, Levels, Wave};
+use crate::s_simulation::simulation_running;
+
+// Loads the level files through the asset server, so edited files are picked up while the game runs.
+// Watching the files needs the file_watcher feature: cargo run --features file_watcher
+pub struct LevelAssetsPlugin;
+
+impl Plugin for LevelAssetsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_asset::<LevelDefinition>()
+        .init_asset::<LevelList>()
+        .init_asset_loader::<LevelLoader>()
+        .init_asset_loader::<LevelListLoader>()
+        .add_systems(Startup, load_level_list)
+        .add_systems(Update, (
+            load_levels,
+            update_levels,
+        ).chain())
+        ;
+    }
+}
+
+#[derive(Resource)]
+pub struct LevelHandles {
+    list: Handle<LevelList>,
+    levels: Vec<Handle<LevelDefinition>>,
+    restart_when_loaded: bool,
+}
+
+fn load_level_list (
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+) {
+    commands.insert_resource(LevelHandles {
+        list: asset_server.load(format!("{}/{}", LEVELS_FOLDER, LEVEL_LIST_FILE)),
+        levels: Vec::new(),
+        restart_when_loaded: false,
+    });
+}
+
+// Loads the level files named in the list, again whenever the list is edited
+fn load_levels (
+    asset_server: Res<AssetServer>,
+    level_lists: Res<Assets<LevelList>>,
+    mut level_handles: ResMut<LevelHandles>,
+    mut level_list_events: EventReader<AssetEvent<LevelList>>,
+) {
+    for event in level_list_events.read() {
+        match event {
+            AssetEvent::LoadedWithDependencies { id } | AssetEvent::Modified { id } if *id == level_handles.list.id() => {
+                let Some(list) = level_lists.get(*id) else { continue };
+                level_handles.levels = list.levels.iter()
+                    .map(|file_name| asset_server.load(format!("{}/{}", LEVELS_FOLDER, file_name)))
+                    .collect();
+                if matches!(event, AssetEvent::Modified { .. }) {
+                    level_handles.restart_when_loaded = true;
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+// Replaces the levels once all files are loaded, and restarts the current wave if its level was edited
+fn update_levels (
+    state: Res<State<AppState>>,
+    wave: Res<Wave>,
+    level_definitions: Res<Assets<LevelDefinition>>,
+    mut levels: ResMut<Levels>,
+    mut level_handles: ResMut<LevelHandles>,
+    mut level_definition_events: EventReader<AssetEvent<LevelDefinition>>,
+    mut restart_wave_writer: EventWriter<EvRestartWave>,
+) {
+    let mut changed = false;
+    for event in level_definition_events.read() {
+        match event {
+            AssetEvent::LoadedWithDependencies { .. } => changed = true,
+            AssetEvent::Modified { id } => {
+                changed = true;
+                let current_level = level_handles.levels.get(levels.index(wave.number));
+                if current_level.is_some_and(|handle| handle.id() == *id) {
+                    level_handles.restart_when_loaded = true;
+                }
+            }
+            _ => {}
+        }
+    }
+    if !changed || level_handles.levels.is_empty() {
+        return;
+    }
+    let loaded: Option<Vec<LevelDefinition>> = level_handles.levels.iter()
+        .map(|handle| level_definitions.get(handle).cloned())
+        .collect();
+    let Some(loaded) = loaded else { return };
+    levels.levels = loaded;
+    if level_handles.restart_when_loaded {
+        level_handles.restart_when_loaded = false;
+        if simulation_running(state) {
+            info!("Level files changed, restarting wave {}", wave.number);
+            restart_wave_writer.send(EvRestartWave);
+        }
+    }
+}
\ No newline at end of file
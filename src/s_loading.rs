@@ -0,0 +1,82 @@
+use bevy::{
+    prelude::*,
+    asset::LoadState,
+};
+use crate::consts::*;
+use crate::c_appstate::AppState;
+use crate::c_sprites::Textures;
+use crate::c_tags::LoadingText;
+use crate::s_game_over::{centered_text, despawn_with};
+
+// Waits for the textures and shaders to load before starting the game, showing the progress meanwhile.
+// Files that fail to load are reported by name, instead of the game starting with invisible sprites.
+pub struct LoadingPlugin;
+
+impl Plugin for LoadingPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(OnEnter(AppState::Loading), start_loading)
+        .add_systems(Update, check_loading.run_if(in_state(AppState::Loading)))
+        .add_systems(OnExit(AppState::Loading), despawn_with::<LoadingText>)
+        ;
+    }
+}
+
+// Every file the game needs, with the handle keeping it loaded
+#[derive(Resource)]
+struct LoadingFiles {
+    files: Vec<(String, UntypedHandle)>,
+    failed: Vec<String>,
+}
+
+fn start_loading (
+    mut commands: Commands,
+    asset_server: Res<AssetServer>,
+    textures: Res<Textures>,
+) {
+    let mut files = textures.files();
+    for shader in [SHIELD_SHADER, BASIC_TEXTURE_SHADER] {
+        files.push((shader.to_string(), asset_server.load::<Shader>(shader).untyped()));
+    }
+    commands.insert_resource(LoadingFiles { files, failed: Vec::new() });
+    commands.spawn(centered_text("LOADING".to_string(), 48.0))
+    .insert(LoadingText);
+}
+
+fn check_loading (
+    asset_server: Res<AssetServer>,
+    mut loading_files: ResMut<LoadingFiles>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut query_text: Query<&mut Text, With<LoadingText>>,
+) {
+    let mut loaded = 0;
+    let mut failed = Vec::new();
+    for (file, handle) in loading_files.files.iter() {
+        match asset_server.get_load_state(handle.id()) {
+            Some(LoadState::Loaded) => loaded += 1,
+            Some(LoadState::Failed) => failed.push(file.clone()),
+            _ => {}
+        }
+    }
+
+    // Report each failed file once
+    for file in failed.iter() {
+        if !loading_files.failed.contains(file) {
+            error!("Could not load assets/{}", file);
+        }
+    }
+    loading_files.failed = failed;
+
+    let total = loading_files.files.len();
+    for mut text in query_text.iter_mut() {
+        text.sections[0].value = if loading_files.failed.is_empty() {
+            format!("LOADING\n{} / {}", loaded, total)
+        } else {
+            format!("MISSING FILES\n{}", loading_files.failed.iter().map(|file| format!("assets/{}", file)).collect::<Vec<_>>().join("\n"))
+        };
+    }
+
+    if loaded == total {
+        next_state.set(AppState::MainMenu);
+    }
+}
\ No newline at end of file
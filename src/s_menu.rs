@@ -0,0 +1,191 @@
+use bevy::prelude::*;
+use bevy::app::AppExit;
+use bevy::ecs::system::SystemParam;
+use crate::c_appstate::{AppState, PausedFrom};
+use crate::c_menu::{MenuAction, MenuButton, MenuSelection};
+use crate::c_score::Score;
+use crate::c_tags::MenuRoot;
+use crate::s_game_over::despawn_with;
+
+// The title screen, and the buttons it shares with the pause overlay.
+// A button is picked with the arrow keys, the D-pad or the mouse, and pressed with Enter, the A button or a click.
+pub struct MenuPlugin;
+
+impl Plugin for MenuPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<MenuSelection>()
+        .add_systems(OnEnter(AppState::MainMenu), spawn_title_menu)
+        .add_systems(OnExit(AppState::MainMenu), despawn_with::<MenuRoot>)
+        .add_systems(Update, (menu_navigation, menu_highlight, menu_activate).chain().run_if(any_with_component::<MenuRoot>()))
+        ;
+    }
+}
+
+const BUTTON_COLOR: Color = Color::rgb(0.15, 0.15, 0.2);
+const BUTTON_SELECTED_COLOR: Color = Color::rgb(0.3, 0.4, 0.7);
+
+#[derive(Clone, Copy)]
+pub enum MenuCommand {
+    Up,
+    Down,
+    Left,
+    Right,
+    Confirm,
+    Back,
+    Pause,
+}
+
+// The keys and gamepad buttons that move through menus. Any gamepad can be used.
+#[derive(SystemParam)]
+pub struct MenuInput<'w> {
+    keyboard_input: Res<'w, Input<KeyCode>>,
+    gamepad_buttons: Res<'w, Input<GamepadButton>>,
+}
+impl MenuInput<'_> {
+    pub fn just_pressed(&self, command: MenuCommand) -> bool {
+        let (keys, buttons): (&[KeyCode], &[GamepadButtonType]) = match command {
+            MenuCommand::Up => (&[KeyCode::Up], &[GamepadButtonType::DPadUp]),
+            MenuCommand::Down => (&[KeyCode::Down], &[GamepadButtonType::DPadDown]),
+            MenuCommand::Left => (&[KeyCode::Left], &[GamepadButtonType::DPadLeft]),
+            MenuCommand::Right => (&[KeyCode::Right], &[GamepadButtonType::DPadRight]),
+            MenuCommand::Confirm => (&[KeyCode::Return, KeyCode::Space], &[GamepadButtonType::South]),
+            MenuCommand::Back => (&[KeyCode::Escape], &[GamepadButtonType::East, GamepadButtonType::Start]),
+            MenuCommand::Pause => (&[KeyCode::Escape], &[GamepadButtonType::Start]),
+        };
+        self.keyboard_input.any_just_pressed(keys.iter().copied())
+        || self.gamepad_buttons.get_just_pressed().any(|button| buttons.contains(&button.button_type))
+    }
+}
+
+// A column of buttons under a title, on top of a backdrop covering the playfield
+pub fn spawn_menu(commands: &mut Commands, selection: &mut MenuSelection, title: &str, subtitle: String, actions: &[MenuAction], backdrop: Color) {
+    selection.0 = 0;
+    commands.spawn(NodeBundle {
+        style: Style {
+            width: Val::Percent(100.0),
+            height: Val::Percent(100.0),
+            flex_direction: FlexDirection::Column,
+            justify_content: JustifyContent::Center,
+            align_items: AlignItems::Center,
+            row_gap: Val::Px(12.0),
+            ..Default::default()
+        },
+        background_color: backdrop.into(),
+        ..Default::default()
+    })
+    .insert(MenuRoot)
+    .with_children(|parent| {
+        parent.spawn(TextBundle::from_section(title, TextStyle {
+            font_size: 64.0,
+            color: Color::WHITE,
+            ..Default::default()
+        }));
+        parent.spawn(TextBundle::from_section(subtitle, TextStyle {
+            font_size: 28.0,
+            color: Color::GRAY,
+            ..Default::default()
+        }));
+        for (index, action) in actions.iter().enumerate() {
+            parent.spawn(ButtonBundle {
+                style: Style {
+                    width: Val::Px(260.0),
+                    height: Val::Px(50.0),
+                    justify_content: JustifyContent::Center,
+                    align_items: AlignItems::Center,
+                    ..Default::default()
+                },
+                background_color: BUTTON_COLOR.into(),
+                ..Default::default()
+            })
+            .insert(MenuButton { action: *action, index })
+            .with_children(|button| {
+                button.spawn(TextBundle::from_section(action.label(), TextStyle {
+                    font_size: 32.0,
+                    color: Color::WHITE,
+                    ..Default::default()
+                }));
+            });
+        }
+    });
+}
+
+fn spawn_title_menu (
+    mut commands: Commands,
+    mut selection: ResMut<MenuSelection>,
+    score: Res<Score>,
+) {
+    let subtitle = if score.0 > 0 { format!("Last score: {}", score.0) } else { String::new() };
+    // Quitting is up to the browser tab on the web
+    let actions: &[MenuAction] = if cfg!(target_arch = "wasm32") {
+        &[MenuAction::Start, MenuAction::Options]
+    } else {
+        &[MenuAction::Start, MenuAction::Options, MenuAction::Quit]
+    };
+    spawn_menu(&mut commands, &mut selection, "COMETBUSTER", subtitle, actions, Color::rgba(0.0, 0.0, 0.0, 0.7));
+}
+
+fn menu_navigation (
+    menu_input: MenuInput,
+    mut selection: ResMut<MenuSelection>,
+    query_buttons: Query<&MenuButton>,
+    query_hovered: Query<(&MenuButton, &Interaction), Changed<Interaction>>,
+) {
+    let count = query_buttons.iter().count();
+    if count == 0 { return; }
+    if menu_input.just_pressed(MenuCommand::Up) {
+        selection.0 = (selection.0 + count - 1) % count;
+    }
+    if menu_input.just_pressed(MenuCommand::Down) {
+        selection.0 = (selection.0 + 1) % count;
+    }
+    for (button, interaction) in query_hovered.iter() {
+        if *interaction != Interaction::None {
+            selection.0 = button.index;
+        }
+    }
+}
+
+fn menu_highlight (
+    selection: Res<MenuSelection>,
+    mut query: Query<(&MenuButton, &mut BackgroundColor)>,
+) {
+    for (button, mut background_color) in query.iter_mut() {
+        let color = if button.index == selection.0 { BUTTON_SELECTED_COLOR } else { BUTTON_COLOR };
+        if background_color.0 != color {
+            background_color.0 = color;
+        }
+    }
+}
+
+#[allow(clippy::too_many_arguments)]
+fn menu_activate (
+    menu_input: MenuInput,
+    selection: Res<MenuSelection>,
+    state: Res<State<AppState>>,
+    paused_from: Option<Res<PausedFrom>>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut exit_writer: EventWriter<AppExit>,
+    query_buttons: Query<&MenuButton>,
+    query_clicked: Query<(&MenuButton, &Interaction), Changed<Interaction>>,
+) {
+    let clicked = query_clicked.iter()
+    .find(|(_, interaction)| **interaction == Interaction::Pressed)
+    .map(|(button, _)| button.action);
+    let confirmed = if menu_input.just_pressed(MenuCommand::Confirm) {
+        query_buttons.iter().find(|button| button.index == selection.0).map(|button| button.action)
+    } else {
+        None
+    };
+    // Backing out of the pause overlay goes back to the game
+    let back = (*state.get() == AppState::Paused && menu_input.just_pressed(MenuCommand::Back)).then_some(MenuAction::Resume);
+
+    match clicked.or(confirmed).or(back) {
+        Some(MenuAction::Start) | Some(MenuAction::Restart) => next_state.set(AppState::SpawnStart),
+        Some(MenuAction::Options) => next_state.set(AppState::Options),
+        Some(MenuAction::Quit) => exit_writer.send(AppExit),
+        Some(MenuAction::Resume) => next_state.set(paused_from.map_or(AppState::InGame, |paused_from| paused_from.0.clone())),
+        Some(MenuAction::QuitToMenu) => next_state.set(AppState::MainMenu),
+        None => {}
+    }
+}
\ No newline at end of file
@@ -1,19 +1,23 @@
 use bevy::prelude::*;
 use crate::consts::*;
-use crate::c_appstate::AppState;
-use crate::c_movement_and_collisions::{Angle, Velocity};
+use crate::c_playfield::Playfield;
+use crate::c_movement_and_collisions::{Angle, AngularVelocity, Velocity};
 use crate::c_tags::{Bullet, GridSprite, Original};
+use crate::s_simulation::SimulationSet;
 
 pub struct MovementPlugin;
 
 impl Plugin for MovementPlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_systems(Update, movement_translation.run_if(in_state(AppState::InGame)))
-        .add_systems(Update, movement_rotation.run_if(in_state(AppState::InGame)))
-        .add_systems(Update, edge_looping.run_if(in_state(AppState::InGame)))
-        .add_systems(Update, bullet_direction_to_angle.run_if(in_state(AppState::InGame)))
-        .add_systems(Update, normalize_angle.run_if(in_state(AppState::InGame)))
+        .add_systems(FixedUpdate, (
+            movement_translation,
+            edge_looping,
+            bullet_direction_to_angle,
+            movement_spin,
+            normalize_angle,
+            movement_rotation,
+        ).chain().in_set(SimulationSet::Movement))
         ;
     }
 }
@@ -28,6 +32,15 @@ fn movement_translation(
     }
 }
 
+fn movement_spin(
+    time: Res<Time>,
+    mut query: Query<(&mut Angle, &AngularVelocity), With<Original>>,
+) {
+    for (mut angle, angular_velocity) in query.iter_mut() {
+        angle.0 += angular_velocity.0 * time.delta_seconds();
+    }
+}
+
 fn movement_rotation(
     mut query_root: Query<(&Angle, &Children, With<Original>)>,
     mut query_children: Query<(&mut Transform, With<GridSprite>)>
@@ -42,21 +55,14 @@ fn movement_rotation(
 }
 
 fn edge_looping(
+    playfield: Res<Playfield>,
     mut query: Query<(&mut Transform, With<Original>)>,
 ) {
+    let torus = playfield.torus();
     for (mut transform, _) in query.iter_mut() {
-        if transform.translation.x < -WINDOW_WIDTH / 2. {
-            transform.translation.x += WINDOW_WIDTH;
-        }
-        if transform.translation.x > WINDOW_WIDTH / 2. {
-            transform.translation.x -= WINDOW_WIDTH;
-        }
-        if transform.translation.y < WINDOW_HEIGHT / 2. {
-            transform.translation.y += WINDOW_HEIGHT;
-        }
-        if transform.translation.y > WINDOW_HEIGHT / 2. {
-            transform.translation.y -= WINDOW_HEIGHT;
-        }
+        let position = torus.wrap(transform.translation.truncate());
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
     }
 }
 
@@ -0,0 +1,218 @@
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+use crate::consts::*;
+use crate::c_appstate::AppState;
+use crate::c_controls::{Action, Binding, Stick};
+use crate::c_players::PlayerId;
+use crate::c_settings::Settings;
+use crate::c_tags::OptionsText;
+use crate::s_game_over::{centered_text, despawn_with};
+use crate::s_menu::{MenuCommand, MenuInput};
+
+// The options screen, opened from the title screen: bindings of each player, volume, screen shake and window mode.
+// A new binding that is already used for something else is refused, and bindings in conflict in the stored file are marked.
+pub struct OptionsPlugin;
+
+impl Plugin for OptionsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<OptionsMenu>()
+        .add_systems(OnEnter(AppState::Options), spawn_options_text)
+        .add_systems(Update, (options_input, update_options_text).chain().run_if(in_state(AppState::Options)))
+        .add_systems(OnExit(AppState::Options), despawn_with::<OptionsText>)
+        ;
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum OptionsRow {
+    Player,
+    Bind(Action),
+    Steering,
+    Volume,
+    ScreenShake,
+    WindowMode,
+    ResetControls,
+    Back,
+}
+
+const ROWS: [OptionsRow; 12] = [
+    OptionsRow::Player,
+    OptionsRow::Bind(Action::Accelerate),
+    OptionsRow::Bind(Action::TurnLeft),
+    OptionsRow::Bind(Action::TurnRight),
+    OptionsRow::Bind(Action::Fire),
+    OptionsRow::Bind(Action::Shield),
+    OptionsRow::Steering,
+    OptionsRow::Volume,
+    OptionsRow::ScreenShake,
+    OptionsRow::WindowMode,
+    OptionsRow::ResetControls,
+    OptionsRow::Back,
+];
+
+const STEERING_CHOICES: [Option<Stick>; 3] = [Some(Stick::Left), Some(Stick::Right), None];
+const WINDOW_MODES: [WindowMode; 3] = [WindowMode::Windowed, WindowMode::BorderlessFullscreen, WindowMode::Fullscreen];
+
+#[derive(Resource, Default)]
+struct OptionsMenu {
+    row: usize,
+    player: PlayerId,
+    rebinding: bool, // Waiting for the key or button of the selected action
+    message: String,
+}
+
+fn spawn_options_text (
+    mut commands: Commands,
+    mut menu: ResMut<OptionsMenu>,
+    settings: Res<Settings>,
+) {
+    *menu = OptionsMenu::default();
+    commands.spawn(centered_text(options_text(&menu, &settings), 28.0))
+    .insert(OptionsText);
+}
+
+// The next item of a list, or the one before, going round at the ends
+fn cycle<T: Copy + PartialEq>(choices: &[T], current: T, step: isize) -> T {
+    let index = choices.iter().position(|choice| *choice == current).unwrap_or(0) as isize;
+    choices[(index + step).rem_euclid(choices.len() as isize) as usize]
+}
+
+#[allow(clippy::too_many_arguments)]
+fn options_input (
+    menu_input: MenuInput,
+    keyboard_input: Res<Input<KeyCode>>,
+    gamepad_buttons: Res<Input<GamepadButton>>,
+    mut menu: ResMut<OptionsMenu>,
+    mut settings: ResMut<Settings>,
+    mut next_state: ResMut<NextState<AppState>>,
+) {
+    let player = menu.player;
+    let row = ROWS[menu.row];
+
+    if menu.rebinding {
+        let OptionsRow::Bind(action) = row else { return; };
+        if keyboard_input.just_pressed(KeyCode::Escape) {
+            menu.rebinding = false;
+            menu.message.clear();
+            return;
+        }
+        let gamepad = settings.controls[player.0].gamepad;
+        let pressed = keyboard_input.get_just_pressed().next().map(|key| Binding::Key(*key))
+        .or_else(|| gamepad_buttons.get_just_pressed()
+            .find(|button| button.gamepad.id == gamepad)
+            .map(|button| Binding::Button(button.button_type)));
+        let Some(binding) = pressed else { return; };
+        menu.rebinding = false;
+        match settings.conflict(player, action, binding) {
+            Some((other_player, other_action)) => {
+                menu.message = format!("{} is already Player {} {}", binding.name(), other_player.0 + 1, other_action.name());
+            }
+            None => {
+                settings.controls[player.0].rebind(action, binding);
+                menu.message.clear();
+            }
+        }
+        return;
+    }
+
+    if menu_input.just_pressed(MenuCommand::Back) {
+        next_state.set(AppState::MainMenu);
+        return;
+    }
+    if menu_input.just_pressed(MenuCommand::Up) {
+        menu.row = (menu.row + ROWS.len() - 1) % ROWS.len();
+    }
+    if menu_input.just_pressed(MenuCommand::Down) {
+        menu.row = (menu.row + 1) % ROWS.len();
+    }
+
+    let step = match (menu_input.just_pressed(MenuCommand::Left), menu_input.just_pressed(MenuCommand::Right)) {
+        (true, false) => -1,
+        (false, true) => 1,
+        _ => 0,
+    };
+    if step != 0 {
+        match row {
+            OptionsRow::Player => {
+                menu.player = PlayerId((player.0 as isize + step).rem_euclid(MAX_PLAYERS as isize) as usize);
+                menu.message.clear();
+            }
+            OptionsRow::Steering => {
+                let controls = &mut settings.controls[player.0];
+                controls.steering = cycle(&STEERING_CHOICES, controls.steering, step);
+            }
+            OptionsRow::Volume => {
+                settings.volume = (settings.volume + 0.1 * step as f32).clamp(0.0, 1.0);
+            }
+            OptionsRow::ScreenShake => {
+                settings.screen_shake = (settings.screen_shake + 0.25 * step as f32).clamp(0.0, 1.0);
+            }
+            OptionsRow::WindowMode => {
+                settings.window_mode = cycle(&WINDOW_MODES, settings.window_mode, step);
+            }
+            _ => {}
+        }
+    }
+
+    if menu_input.just_pressed(MenuCommand::Confirm) {
+        match row {
+            OptionsRow::Bind(action) => {
+                menu.rebinding = true;
+                menu.message = format!("Press a key or gamepad button for {}, Esc to cancel", action.name());
+            }
+            OptionsRow::ResetControls => {
+                settings.controls[player.0] = player.profile().controls;
+                menu.message = format!("Controls of Player {} reset", player.0 + 1);
+            }
+            OptionsRow::Back => {
+                next_state.set(AppState::MainMenu);
+            }
+            _ => {}
+        }
+    }
+}
+
+fn options_text(menu: &OptionsMenu, settings: &Settings) -> String {
+    let player = menu.player;
+    let controls = &settings.controls[player.0];
+    let mut text = String::from("OPTIONS\n");
+    for (index, row) in ROWS.iter().enumerate() {
+        let line = match row {
+            OptionsRow::Player => format!("Player: {}", player.0 + 1),
+            OptionsRow::Bind(action) => {
+                let bindings: Vec<String> = controls.bindings_of(*action)
+                .map(|binding| match settings.conflict(player, *action, *binding) {
+                    Some(_) => format!("{} (!)", binding.name()),
+                    None => binding.name(),
+                })
+                .collect();
+                format!("{}: {}", action.name(), bindings.join(", "))
+            }
+            OptionsRow::Steering => match controls.steering {
+                Some(stick) => format!("Steering stick: {:?}", stick),
+                None => "Steering stick: Off".to_string(),
+            },
+            OptionsRow::Volume => format!("Volume: {:.0} %", settings.volume * 100.0),
+            OptionsRow::ScreenShake => format!("Screen shake: {:.0} %", settings.screen_shake * 100.0),
+            OptionsRow::WindowMode => format!("Window: {:?}", settings.window_mode),
+            OptionsRow::ResetControls => format!("Reset controls of Player {}", player.0 + 1),
+            OptionsRow::Back => "Back".to_string(),
+        };
+        let cursor = if index == menu.row { "> " } else { "" };
+        text.push_str(&format!("\n{}{}", cursor, line));
+    }
+    text.push_str(&format!("\n\n{}\nUp/Down: choose   Left/Right: change   Enter/A: rebind   Esc/B: back", menu.message));
+    text
+}
+
+fn update_options_text (
+    menu: Res<OptionsMenu>,
+    settings: Res<Settings>,
+    mut query: Query<&mut Text, With<OptionsText>>,
+) {
+    if !menu.is_changed() && !settings.is_changed() { return; }
+    for mut text in query.iter_mut() {
+        text.sections[0].value = options_text(&menu, &settings);
+    }
+}
\ No newline at end of file
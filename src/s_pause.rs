@@ -1,29 +1,46 @@
 use bevy::prelude::*;
-use crate::c_appstate::AppState;
+use crate::c_appstate::{AppState, PausedFrom};
+use crate::c_menu::{MenuAction, MenuSelection};
+use crate::c_tags::MenuRoot;
+use crate::s_game_over::despawn_with;
+use crate::s_menu::{spawn_menu, MenuCommand, MenuInput};
 
+// Escape or the Start button freezes the game, also between waves, under an overlay to resume, restart or quit to the title screen.
+// Picking and pressing the buttons is left to MenuPlugin.
 pub struct PausePlugin;
 
 impl Plugin for PausePlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_systems(Update, pause.run_if(in_state(AppState::InGame)))
-        .add_systems(Update, pause.run_if(in_state(AppState::Paused)))
+        .add_systems(Update, pause.run_if(in_state(AppState::InGame).or_else(in_state(AppState::WaveTransition))))
+        .add_systems(OnEnter(AppState::Paused), spawn_pause_menu)
+        .add_systems(OnExit(AppState::Paused), despawn_with::<MenuRoot>)
         ;
     }
 }
 
 fn pause (
+    mut commands: Commands,
+    menu_input: MenuInput,
     state: Res<State<AppState>>,
     mut next_state: ResMut<NextState<AppState>>,
-    mut keyboard_input: ResMut<Input<KeyCode>>,
 ) {
-    if keyboard_input.just_pressed(KeyCode::Escape) {
-        if matches!(state.get(), AppState::Paused) {
-            next_state.set(AppState::InGame);
-        }
-        if matches!(state.get(), AppState::InGame) {
-            next_state.set(AppState::Paused);
-        }
-        keyboard_input.clear();
+    if menu_input.just_pressed(MenuCommand::Pause) {
+        commands.insert_resource(PausedFrom(state.get().clone()));
+        next_state.set(AppState::Paused);
     }
+}
+
+fn spawn_pause_menu (
+    mut commands: Commands,
+    mut selection: ResMut<MenuSelection>,
+) {
+    spawn_menu(
+        &mut commands,
+        &mut selection,
+        "PAUSED",
+        String::new(),
+        &[MenuAction::Resume, MenuAction::Restart, MenuAction::QuitToMenu],
+        Color::rgba(0.0, 0.0, 0.0, 0.5),
+    );
 }
\ No newline at end of file
@@ -0,0 +1,113 @@
+use bevy::{prelude::*, app::AppExit};
+use crate::consts::*;
+use crate::helpers::arg_value;
+use crate::c_appstate::AppState;
+use crate::c_controls::{ControlFrame, ControlState};
+use crate::c_gamerng::GameRng;
+use crate::c_lifetime_spawntime::SimTick;
+use crate::c_players::{LocalPlayers, PlayerId};
+use crate::c_replay::{Replay, ReplayPlayback, ReplayRecording};
+use crate::c_tags::Player;
+use crate::s_control::read_controls;
+use crate::s_simulation::SimulationSet;
+
+// Records the controls to a file with "--record FILE", or plays them back with "--replay FILE".
+// Must be added before SimulationPlugin, so a replay can set the seed before a random one is picked.
+pub struct ReplayPlugin;
+
+impl Plugin for ReplayPlugin {
+    fn build(&self, app: &mut App) {
+        if let Some(path) = arg_value("--replay") {
+            match Replay::load(&path) {
+                Ok(replay) => {
+                    info!("Playing back {}", path);
+                    play_back(app, replay);
+                }
+                Err(error) => error!("Could not load replay {}: {}", path, error),
+            }
+        }
+        else if let Some(path) = arg_value("--record") {
+            record(app, path);
+        }
+    }
+}
+
+// Feeds the controls of the replay to the ships in place of the input devices
+pub fn play_back(app: &mut App, replay: Replay) {
+    app
+    .insert_resource(GameRng::from_seed(replay.seed))
+    .insert_resource(LocalPlayers(replay.players))
+    .insert_resource(ReplayPlayback { replay, finished: false })
+    .add_systems(FixedUpdate, play_back_controls.in_set(SimulationSet::Input))
+    ;
+}
+
+// Records the controls to the file. It is saved every few seconds and on game over, not only on exit,
+// so a crash or a killed process still leaves a recording to reproduce it with.
+pub fn record(app: &mut App, path: String) {
+    app
+    .add_systems(Startup, start_recording(path))
+    .add_systems(FixedUpdate, (
+        record_controls,
+        save_recording.run_if(save_due),
+    ).chain().after(read_controls).in_set(SimulationSet::Input))
+    .add_systems(OnEnter(AppState::GameOver), save_recording)
+    .add_systems(Last, save_recording.run_if(on_event::<AppExit>()))
+    ;
+}
+
+// The seed is only known once SimulationPlugin has picked it
+fn start_recording(path: String) -> impl FnMut(Commands, Res<GameRng>, Res<LocalPlayers>) {
+    move |mut commands: Commands, game_rng: Res<GameRng>, local_players: Res<LocalPlayers>| {
+        info!("Recording to {}", path);
+        commands.insert_resource(ReplayRecording { path: path.clone(), replay: Replay::new(game_rng.seed, local_players.0), saved_ticks: 0 });
+    }
+}
+
+fn record_controls(
+    mut recording: ResMut<ReplayRecording>,
+    query: Query<(&PlayerId, &ControlState), With<Player>>,
+) {
+    // Players waiting to respawn hold no controls
+    let mut frames = vec![ControlFrame::default(); recording.replay.players];
+    for (player_id, control_state) in query.iter() {
+        if let Some(frame) = frames.get_mut(player_id.0) {
+            *frame = control_state.current;
+        }
+    }
+    recording.replay.ticks.push(frames);
+}
+
+fn save_due(recording: Res<ReplayRecording>) -> bool {
+    recording.replay.ticks.len() >= recording.saved_ticks + (REPLAY_SAVE_SECONDS * TICKS_PER_SECOND) as usize
+}
+
+fn save_recording(
+    mut recording: ResMut<ReplayRecording>,
+) {
+    match recording.replay.save(&recording.path) {
+        Ok(()) => {
+            recording.saved_ticks = recording.replay.ticks.len();
+            debug!("Saved {} ticks to {}", recording.saved_ticks, recording.path);
+        }
+        Err(error) => error!("Could not save replay {}: {}", recording.path, error),
+    }
+}
+
+fn play_back_controls(
+    sim_tick: Res<SimTick>,
+    mut playback: ResMut<ReplayPlayback>,
+    mut query: Query<(&PlayerId, &mut ControlState), With<Player>>,
+) {
+    let frames = playback.replay.frames(sim_tick.0).cloned();
+    if frames.is_none() && !playback.finished {
+        info!("Replay finished after {} ticks", playback.replay.ticks.len());
+        playback.finished = true;
+    }
+
+    for (player_id, mut control_state) in query.iter_mut() {
+        // All controls are released once the recording runs out
+        let frame = frames.as_ref().and_then(|frames| frames.get(player_id.0)).copied().unwrap_or_default();
+        control_state.advance(frame);
+    }
+}
\ No newline at end of file
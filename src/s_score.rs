@@ -0,0 +1,92 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use crate::consts::*;
+use crate::c_appstate::AppState;
+use crate::c_events::{EvDestroy, EvKill};
+use crate::c_lifetime_spawntime::{ticks_from_seconds, SimTick};
+use crate::c_players::{FiredBy, LocalPlayers, PlayerId};
+use crate::c_score::{Kills, Lives, RespawnAt, Score};
+use crate::c_sprites::AsteroidSize;
+use crate::c_tags::Player;
+use crate::s_collision_response::{apply_collision_rules, destroy_response};
+use crate::s_simulation::SimulationSet;
+
+pub struct ScorePlugin;
+
+impl Plugin for ScorePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<Score>()
+        .init_resource::<Kills>()
+        .init_resource::<Lives>()
+        .init_resource::<RespawnAt>()
+        .add_systems(OnEnter(AppState::SpawnStart), reset_score_and_lives)
+        .add_systems(FixedUpdate, (award_points, award_kills, lose_life)
+            .after(apply_collision_rules)
+            .before(destroy_response)
+            .in_set(SimulationSet::Response))
+        ;
+    }
+}
+
+fn reset_score_and_lives (
+    local_players: Res<LocalPlayers>,
+    mut score: ResMut<Score>,
+    mut kills: ResMut<Kills>,
+    mut lives: ResMut<Lives>,
+    mut respawn_at: ResMut<RespawnAt>,
+) {
+    score.0 = 0;
+    *kills = Kills::default();
+    lives.remaining = lives.starting * local_players.0 as u32;
+    *respawn_at = RespawnAt::default();
+}
+
+// Runs before the entities are despawned, while their size can still be looked at
+fn award_points (
+    mut score: ResMut<Score>,
+    mut destroy_reader: EventReader<EvDestroy>,
+    query: Query<&AsteroidSize>,
+) {
+    // An asteroid hit by two bullets on the same tick only counts once
+    let destroyed: HashSet<Entity> = destroy_reader.read().map(|destroy| destroy.entity).collect();
+    for asteroid_size in query.iter_many(destroyed.iter()) {
+        score.0 += asteroid_size.points();
+    }
+}
+
+// Credits the shooter when a bullet destroys the ship of another player
+fn award_kills (
+    mut kills: ResMut<Kills>,
+    mut kill_reader: EventReader<EvKill>,
+    query_bullets: Query<&FiredBy>,
+    query_players: Query<&PlayerId, With<Player>>,
+) {
+    for kill in kill_reader.read() {
+        let (Ok(fired_by), Ok(victim)) = (query_bullets.get(kill.bullet), query_players.get(kill.ship)) else { continue; };
+        if fired_by.0 != *victim {
+            kills.0[fired_by.0.0] += 1;
+        }
+    }
+}
+
+// The game is over once the ships in play were the last ones left
+fn lose_life (
+    sim_tick: Res<SimTick>,
+    mut lives: ResMut<Lives>,
+    mut respawn_at: ResMut<RespawnAt>,
+    mut next_state: ResMut<NextState<AppState>>,
+    mut destroy_reader: EventReader<EvDestroy>,
+    query: Query<&PlayerId, With<Player>>,
+) {
+    let destroyed: HashSet<Entity> = destroy_reader.read().map(|destroy| destroy.entity).collect();
+    for player_id in query.iter_many(destroyed.iter()) {
+        lives.remaining = lives.remaining.saturating_sub(1);
+        if lives.remaining == 0 {
+            next_state.set(AppState::GameOver);
+        }
+        else {
+            respawn_at.0[player_id.0] = sim_tick.0 + ticks_from_seconds(RESPAWN_DELAY_SECONDS);
+        }
+    }
+}
\ No newline at end of file
@@ -1,7 +1,10 @@
 use bevy::prelude::*;
 use crate::helpers::*;
-use crate::c_appstate::AppState;
+use crate::s_simulation::simulation_running;
+use crate::c_gamerng::GameRng;
+use crate::c_events::EvSpawnBounceEffect;
 use crate::c_screenshake::ScreenShake;
+use crate::c_settings::Settings;
 use crate::c_tags::CameraWorld;
 
 pub struct ScreenShakePlugin;
@@ -9,15 +12,27 @@ pub struct ScreenShakePlugin;
 impl Plugin for ScreenShakePlugin {
     fn build(&self, app: &mut App) {
         app
-        .add_systems(Update, screen_shake.run_if(in_state(AppState::InGame)))
+        .add_systems(Update, spawn_screen_shake.run_if(simulation_running))
+        .add_systems(Update, screen_shake.run_if(simulation_running))
         ;
     }
 }
 
+fn spawn_screen_shake (
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut bounce_effect_reader: EventReader<EvSpawnBounceEffect>,
+) {
+    for bounce_effect in bounce_effect_reader.read() {
+        commands.spawn(ScreenShake{amplitude: (0.0 + bounce_effect.change_of_momentum / 5000.0).min(10.0) * settings.screen_shake, ..Default::default()});
+    }
+}
+
 fn screen_shake (
     mut commands: Commands,
     query: Query<(Entity, &ScreenShake)>,
     mut query_camera: Query<(&mut Transform, With<CameraWorld>)>,
+    mut game_rng: ResMut<GameRng>,
 ) {
     for (mut transform, _) in query_camera.iter_mut() {
         transform.translation.x = 0.0;
@@ -27,8 +42,8 @@ fn screen_shake (
                 commands.entity(entity).despawn();
             } else {
                 let current_amplitude: f32 = screen_shake.amplitude * (screen_shake.duration.as_secs_f32() - screen_shake.start_time.elapsed().as_secs_f32()) / screen_shake.duration.as_secs_f32();
-                transform.translation.x += rf32(-current_amplitude, current_amplitude);
-                transform.translation.y += rf32(-current_amplitude, current_amplitude);
+                transform.translation.x += rf32(&mut game_rng.cosmetic, -current_amplitude, current_amplitude);
+                transform.translation.y += rf32(&mut game_rng.cosmetic, -current_amplitude, current_amplitude);
             }
         }
     }
@@ -0,0 +1,41 @@
+use bevy::prelude::*;
+use bevy::audio::GlobalVolume;
+use bevy::window::PrimaryWindow;
+use crate::c_settings::Settings;
+
+// Loads the stored settings at start, applies them, and stores them again whenever they change.
+// Without this plugin, like in the headless simulation and tests, the defaults are used and nothing is stored.
+pub struct SettingsPlugin;
+
+impl Plugin for SettingsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(Settings::load())
+        .add_systems(Update, (apply_settings, save_settings).run_if(resource_changed::<Settings>()))
+        ;
+    }
+}
+
+// Only sounds started after a change of volume play at the new volume
+fn apply_settings (
+    mut commands: Commands,
+    settings: Res<Settings>,
+    mut query_window: Query<&mut Window, With<PrimaryWindow>>,
+) {
+    commands.insert_resource(GlobalVolume::new(settings.volume));
+    for mut window in query_window.iter_mut() {
+        if window.mode != settings.window_mode {
+            window.mode = settings.window_mode;
+        }
+    }
+}
+
+fn save_settings (
+    settings: Res<Settings>,
+) {
+    // Nothing to store right after loading
+    if settings.is_added() { return; }
+    if let Err(error) = settings.save() {
+        error!("Could not save settings: {}", error);
+    }
+}
\ No newline at end of file
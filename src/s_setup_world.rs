@@ -1,7 +1,9 @@
-use bevy::prelude::*;
-use crate::c_appstate::AppState;
-//use crate::consts::*;
-//use crate::c_sprites::Textures;
+use bevy::{
+    prelude::*,
+    render::camera::{ScalingMode, Viewport},
+    window::PrimaryWindow,
+};
+use crate::c_playfield::Playfield;
 use crate::c_tags::CameraWorld;
 
 pub struct SetupWorldPlugin;
@@ -9,29 +11,50 @@ pub struct SetupWorldPlugin;
 impl Plugin for SetupWorldPlugin {
     fn build(&self, app: &mut App) {
         app
+        .insert_resource(ClearColor(Color::BLACK)) // Color of the letterbox bars
         .add_systems(Startup, setup_world)
+        .add_systems(Update, fit_camera_to_window)
         ;
     }
 }
 
 fn setup_world (
     mut commands: Commands,
-    mut next_state: ResMut<NextState<AppState>>,
-//    asset_server: Res<AssetServer>,
 ) {
     commands.spawn(Camera2dBundle::default())
     .insert(CameraWorld);
+}
 
-/*
-commands.insert_resource(Textures {
-    ship: asset_server.load(SHIP_SPRITE),
-    shield: asset_server.load(SHIELD_SPRITE),
-    bullet: asset_server.load(BULLET_SPRITE),
-    asteroid_1: asset_server.load(ASTEROID_1_SPRITE),
-    background: asset_server.load(BACKGROUND_SPRITE),
-    color_gradients: asset_server.load(TEXTURE_SPRITE),
-});
-*/
+// Scales the whole playfield to fit the window, keeping its aspect ratio.
+// The camera only draws to the part of the window the playfield covers, leaving bars on the sides or on top and bottom.
+fn fit_camera_to_window (
+    playfield: Res<Playfield>,
+    query_window: Query<&Window, With<PrimaryWindow>>,
+    mut query_camera: Query<(&mut Camera, &mut OrthographicProjection), With<CameraWorld>>,
+) {
+    let Ok(window) = query_window.get_single() else { return };
+    let window_size = Vec2::new(window.physical_width() as f32, window.physical_height() as f32);
+    let scale = (window_size.x / playfield.width).min(window_size.y / playfield.height);
+    let viewport_size = (playfield.size() * scale).floor();
+    if viewport_size.x < 1.0 || viewport_size.y < 1.0 {
+        return; // Minimized
+    }
+    let viewport = Viewport {
+        physical_position: ((window_size - viewport_size) / 2.0).as_uvec2(),
+        physical_size: viewport_size.as_uvec2(),
+        ..default()
+    };
 
-    next_state.set(AppState::SpawnStart);
+    for (mut camera, mut projection) in query_camera.iter_mut() {
+        // Only touch the camera when something changed, so change detection stays quiet
+        let unchanged = camera.viewport.as_ref().is_some_and(|current| {
+            current.physical_position == viewport.physical_position && current.physical_size == viewport.physical_size
+        });
+        if !unchanged {
+            camera.viewport = Some(viewport.clone());
+        }
+        if !matches!(projection.scaling_mode, ScalingMode::Fixed { width, height } if width == playfield.width && height == playfield.height) {
+            projection.scaling_mode = ScalingMode::Fixed { width: playfield.width, height: playfield.height };
+        }
+    }
 }
\ No newline at end of file
@@ -0,0 +1,69 @@
+use bevy::prelude::*;
+use bevy::utils::HashSet;
+use crate::c_events::{EvCollision, EvDestroy};
+use crate::c_sim_stats::SimStats;
+use crate::c_sprites::AsteroidSize;
+use crate::c_tags::{Bullet, Player, Shield};
+use crate::s_collision_response::{apply_collision_rules, destroy_response};
+use crate::s_simulation::SimulationSet;
+
+// Collects SimStats for the headless simulation
+pub struct SimStatsPlugin;
+
+impl Plugin for SimStatsPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .init_resource::<SimStats>()
+        .add_systems(FixedUpdate, count_destroyed.after(apply_collision_rules).before(destroy_response).in_set(SimulationSet::Response))
+        .add_systems(FixedUpdate, measure_collisions.in_set(SimulationSet::Response))
+        .add_systems(FixedUpdate, (count_shots, count_ticks).in_set(SimulationSet::SpawnDespawn))
+        ;
+    }
+}
+
+// Runs before the entities are despawned, while they can still be looked at
+fn count_destroyed (
+    mut stats: ResMut<SimStats>,
+    mut destroy_reader: EventReader<EvDestroy>,
+    query: Query<(Option<&AsteroidSize>, Option<&Player>)>,
+) {
+    // An entity hit by two objects on the same tick is only destroyed once
+    let destroyed: HashSet<Entity> = destroy_reader.read().map(|destroy| destroy.entity).collect();
+    for (asteroid_size, player) in query.iter_many(destroyed.iter()) {
+        match asteroid_size {
+            Some(AsteroidSize::Big) => stats.big_asteroids_destroyed += 1,
+            Some(AsteroidSize::Medium) => stats.medium_asteroids_destroyed += 1,
+            Some(AsteroidSize::Small) => stats.small_asteroids_destroyed += 1,
+            None => {}
+        }
+        if player.is_some() {
+            stats.lives_lost += 1;
+        }
+    }
+}
+
+fn measure_collisions (
+    mut stats: ResMut<SimStats>,
+    mut collision_reader: EventReader<EvCollision>,
+) {
+    for collision in collision_reader.read() {
+        stats.strongest_collision = stats.strongest_collision.max(collision.impulse.length());
+    }
+}
+
+fn count_shots (
+    mut stats: ResMut<SimStats>,
+    query: Query<(), Added<Bullet>>,
+) {
+    stats.shots_fired += query.iter().count() as u32;
+}
+
+fn count_ticks (
+    mut stats: ResMut<SimStats>,
+    query_shield: Query<(), With<Shield>>,
+) {
+    stats.ticks += 1;
+    if !query_shield.is_empty() {
+        stats.shield_ticks += 1;
+    }
+}
\ No newline at end of file
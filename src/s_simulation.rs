@@ -0,0 +1,52 @@
+use bevy::prelude::*;
+use crate::consts::*;
+use crate::c_appstate::AppState;
+use crate::c_gamerng::GameRng;
+use crate::c_lifetime_spawntime::SimTick;
+
+// Runs the gameplay on a fixed tick, so the outcome doesn't depend on the frame rate.
+// Every gameplay system runs in one of the sets below, and the sets always run in this order,
+// so two runs with the same inputs give the same world state.
+pub struct SimulationPlugin;
+
+#[derive(SystemSet, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SimulationSet {
+    Tick,
+    Input,
+    Control,
+    Energy,
+    Movement,
+    Collision,
+    Response,
+    SpawnDespawn,
+}
+
+impl Plugin for SimulationPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(Time::<Fixed>::from_hz(TICKS_PER_SECOND as f64))
+        .init_resource::<SimTick>()
+        .init_resource::<GameRng>()
+        .configure_sets(FixedUpdate, (
+            SimulationSet::Tick,
+            SimulationSet::Input,
+            SimulationSet::Control,
+            SimulationSet::Energy,
+            SimulationSet::Movement,
+            SimulationSet::Collision,
+            SimulationSet::Response,
+            SimulationSet::SpawnDespawn,
+        ).chain().run_if(simulation_running))
+        .add_systems(FixedUpdate, advance_tick.in_set(SimulationSet::Tick))
+        ;
+    }
+}
+
+// The simulation keeps running between waves
+pub fn simulation_running(state: Res<State<AppState>>) -> bool {
+    matches!(state.get(), AppState::InGame | AppState::WaveTransition)
+}
+
+fn advance_tick(mut sim_tick: ResMut<SimTick>) {
+    sim_tick.0 += 1;
+}
\ No newline at end of file
@@ -0,0 +1,193 @@
+use bevy::{
+    prelude::*,
+    sprite::MaterialMesh2dBundle,
+};
+use rand::Rng;
+use crate::c_appstate::AppState;
+use crate::c_gamerng::GameRng;
+use crate::c_playfield::Playfield;
+use crate::c_players::PlayerId;
+use crate::c_sprite_manifest::SpriteManifest;
+use crate::s_simulation::simulation_running;
+use crate::c_sprites::{AsteroidSize, RenderCache, SpriteMaterial, SpriteType, Textures, WrapCopy, WrapSprite};
+use crate::c_events::{EvCmpSpawnSprites, EvSpawnBounceEffect};
+use crate::c_chargelevel::ChargeLevel;
+use crate::c_movement_and_collisions::Angle;
+use crate::c_tags::{Background, GridSprite};
+use crate::c_lifetime_spawntime::{Lifetime, SimTick, SpawnTime};
+
+// Everything that is drawn for the gameplay entities. Kept out of the gameplay plugins,
+// so the simulation can run without a window or any rendering assets.
+pub struct SpritesPlugin;
+
+impl Plugin for SpritesPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(SpriteManifest::load())
+        .init_resource::<Textures>()
+        .add_systems(OnExit(AppState::Loading), build_render_cache)
+        .add_systems(Startup, spawn_background)
+        .add_systems(Update, resize_background)
+        .add_systems(Update, (
+            spawn_sprites,
+            apply_deferred,
+            update_wrap_copies,
+        ).chain().run_if(simulation_running))
+        .add_systems(Update, spawn_bounce_sparks.run_if(simulation_running))
+        ;
+    }
+}
+
+// The textures are loaded by now, so atlas regions can be measured against their images
+fn build_render_cache(world: &mut World) {
+    world.init_resource::<RenderCache>();
+}
+
+fn spawn_background (
+    mut commands: Commands,
+    textures: Res<Textures>,
+    playfield: Res<Playfield>,
+){
+    commands.spawn(SpriteBundle {
+        texture: textures.background.clone_weak(),
+        sprite: Sprite{
+            flip_x: false,
+            flip_y: false,
+            color: Color::rgb(1.0, 1.0, 1.0),
+            custom_size: Some(playfield.size() + 10.0),
+            ..Default::default()
+        },
+        ..Default::default()
+    })
+    .insert(Background);
+}
+
+// Stretches the background over a new playfield
+fn resize_background (
+    playfield: Res<Playfield>,
+    mut query_background: Query<&mut Sprite, With<Background>>,
+) {
+    if !playfield.is_changed() {
+        return;
+    }
+    for mut sprite in query_background.iter_mut() {
+        sprite.custom_size = Some(playfield.size() + 10.0);
+    }
+}
+
+// What picks the look of a new entity, and the ship a shield belongs to
+type NewSprite = (Entity, &'static SpriteType, Option<&'static AsteroidSize>, Option<&'static ChargeLevel>, Option<&'static Parent>);
+
+// Gives new entities their mesh and material from the cache, picking one of the variants at random.
+// Ships, and the shields they hold, are drawn in the colors of their player.
+// The sprites themselves are spawned by update_wrap_copies.
+fn spawn_sprites (
+    mut commands: Commands,
+    mut game_rng: ResMut<GameRng>,
+    render_cache: Res<RenderCache>,
+    query: Query<NewSprite, With<EvCmpSpawnSprites>>,
+    query_player_ids: Query<&PlayerId>,
+){
+    for (entity, sprite_type, asteroid_size, charge_level, parent) in query.iter() {
+        let player_id = query_player_ids.get(entity)
+            .or_else(|_| query_player_ids.get(parent.map_or(Entity::PLACEHOLDER, |parent| parent.get())))
+            .ok()
+            .copied();
+        let variants = render_cache.variants(sprite_type, asteroid_size, charge_level, player_id);
+        let variant = &variants[game_rng.cosmetic.gen_range(0..variants.len())];
+        commands.entity(entity)
+        .remove::<EvCmpSpawnSprites>()
+        .insert(WrapSprite::new(variant));
+    }
+}
+
+// What decides where the copies of a sprite go, and the copies already there
+type WrappedSprite = (Entity, &'static WrapSprite, &'static Transform, Option<&'static Angle>, Option<&'static Parent>, Option<&'static Children>);
+
+// Keeps one sprite for every place an entity shows on the playfield.
+// Most entities only need one, the ones crossing an edge get a copy on the other side, and up to three in a corner.
+fn update_wrap_copies (
+    mut commands: Commands,
+    playfield: Res<Playfield>,
+    query: Query<WrappedSprite>,
+    query_parents: Query<&Transform>,
+    query_copies: Query<&WrapCopy>,
+) {
+    let torus = playfield.torus();
+    for (entity, wrap_sprite, transform, angle, parent, children) in query.iter() {
+        // Shields are children of their ship, so their position is relative to it
+        let mut position = transform.translation.truncate();
+        if let Some(parent_transform) = parent.and_then(|parent| query_parents.get(parent.get()).ok()) {
+            position += parent_transform.translation.truncate();
+        }
+        let offsets = torus.aabb_copies(position, wrap_sprite.half_size);
+
+        let mut existing = Vec::<Vec2>::new();
+        for child in children.into_iter().flatten() {
+            let Ok(copy) = query_copies.get(*child) else { continue };
+            if offsets.contains(&copy.0) {
+                existing.push(copy.0);
+            } else {
+                commands.entity(*child).despawn_recursive();
+            }
+        }
+
+        let rotation = Quat::from_rotation_z(angle.map_or(0.0, |angle| angle.0));
+        for offset in offsets.into_iter().filter(|offset| !existing.contains(offset)) {
+            let sprite = &wrap_sprite.sprite;
+            let transform = Transform {
+                translation: offset.extend(sprite.z),
+                rotation,
+                ..Default::default()
+            };
+            let copy = match &sprite.material {
+                SpriteMaterial::Basic(material) => commands.spawn(MaterialMesh2dBundle {
+                    mesh: sprite.mesh.clone(),
+                    material: material.clone(),
+                    transform,
+                    ..Default::default()
+                }).id(),
+                SpriteMaterial::Shield(material) => commands.spawn(MaterialMesh2dBundle {
+                    mesh: sprite.mesh.clone(),
+                    material: material.clone(),
+                    transform,
+                    ..Default::default()
+                }).id(),
+            };
+            commands.entity(copy)
+            .insert(GridSprite)
+            .insert(WrapCopy(offset));
+            commands.entity(entity).add_child(copy);
+        }
+    }
+}
+
+// Debug sparks showing where objects bounced
+fn spawn_bounce_sparks (
+    mut commands: Commands,
+    sim_tick: Res<SimTick>,
+    mut bounce_effect_reader: EventReader<EvSpawnBounceEffect>,
+) {
+    for bounce_effect in bounce_effect_reader.read() {
+        commands.spawn(SpriteBundle {
+            sprite: Sprite {
+                color: Color::rgb(1.0, 1.0, 1.0),
+                flip_x: false,
+                flip_y: false,
+                custom_size: Some(Vec2::new(5.0,5.0)),
+                ..Default::default()
+            },
+            transform: Transform {
+                translation: Vec3::new(
+                    bounce_effect.contact_point.x,
+                    bounce_effect.contact_point.y,
+                    100.0,
+                ),
+                ..Default::default()
+            },
+            ..Default::default()
+        })
+        .insert(SpawnTime(sim_tick.0))
+        .insert(Lifetime::from_seconds(1.0));
+    }
+}
\ No newline at end of file
@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+use crate::c_appstate::AppState;
+use crate::c_levels::{Levels, Wave};
+use crate::c_tags::WaveBannerText;
+use crate::s_game_over::{centered_text, despawn_with};
+
+// Shows the number and name of the coming wave during the transition between waves.
+// Pausing in between keeps the banner up, only starting and ending a transition spawn and remove it.
+pub struct WaveBannerPlugin;
+
+impl Plugin for WaveBannerPlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .add_systems(OnTransition { from: AppState::SpawnStart, to: AppState::WaveTransition }, spawn_wave_banner)
+        .add_systems(OnTransition { from: AppState::InGame, to: AppState::WaveTransition }, spawn_wave_banner)
+        .add_systems(OnTransition { from: AppState::WaveTransition, to: AppState::InGame }, despawn_with::<WaveBannerText>)
+        .add_systems(OnTransition { from: AppState::WaveTransition, to: AppState::GameOver }, despawn_with::<WaveBannerText>)
+        // Restarting or quitting from the pause menu
+        .add_systems(OnEnter(AppState::SpawnStart), despawn_with::<WaveBannerText>)
+        .add_systems(OnEnter(AppState::MainMenu), despawn_with::<WaveBannerText>)
+        ;
+    }
+}
+
+fn spawn_wave_banner (
+    mut commands: Commands,
+    wave: Res<Wave>,
+    levels: Res<Levels>,
+) {
+    commands.spawn(centered_text(format!("WAVE {}\n{}", wave.number, levels.get(wave.number).name), 48.0))
+    .insert(WaveBannerText);
+}
\ No newline at end of file
@@ -0,0 +1,197 @@
+use bevy::{
+    prelude::*,
+    ecs::system::EntityCommands,
+};
+use crate::consts::*;
+use rand::Rng;
+use crate::helpers::*;
+use crate::c_appstate::AppState;
+use crate::c_bundles::{AsteroidBigBundle, AsteroidMediumBundle, AsteroidSmallBundle};
+use crate::c_gamerng::GameRng;
+use crate::c_events::EvRestartWave;
+use crate::c_levels::{AsteroidKind, Levels, Wave};
+use crate::c_lifetime_spawntime::{ticks_from_seconds, SimTick};
+use crate::c_movement_and_collisions::{CollisionType, ConvexHull, Mass, Velocity};
+use crate::c_playfield::Playfield;
+use crate::torus::Torus;
+use crate::c_shipstats::{Invulnerable, ShipStats};
+use crate::c_sprites::AsteroidSize;
+use crate::c_tags::{Original, Player};
+use crate::s_simulation::SimulationSet;
+
+// Spawns the asteroid field of each wave from the level definitions, and starts the next wave when the field is cleared
+pub struct WavePlugin;
+
+impl Plugin for WavePlugin {
+    fn build(&self, app: &mut App) {
+        app
+        .insert_resource(Levels::load())
+        .init_resource::<Wave>()
+        .add_systems(OnEnter(AppState::SpawnStart), reset_wave)
+        .add_systems(FixedUpdate, (
+            restart_wave,
+            resize_playfield,
+            advance_wave,
+        ).chain().in_set(SimulationSet::SpawnDespawn))
+        ;
+    }
+}
+
+fn reset_wave(mut wave: ResMut<Wave>) {
+    *wave = Wave::default();
+}
+
+// Clears the field and spawns the current level again, like when its file has been edited
+fn restart_wave (
+    mut commands: Commands,
+    mut wave: ResMut<Wave>,
+    mut next_state: ResMut<NextState<AppState>>,
+    sim_tick: Res<SimTick>,
+    mut restart_wave_reader: EventReader<EvRestartWave>,
+    query_asteroids: Query<Entity, With<AsteroidSize>>,
+) {
+    if restart_wave_reader.read().count() == 0 {
+        return;
+    }
+    for entity in query_asteroids.iter() {
+        commands.entity(entity).despawn_recursive();
+    }
+    wave.in_progress = false;
+    wave.next_wave_at = sim_tick.0 + ticks_from_seconds(WAVE_TRANSITION_SECONDS);
+    next_state.set(AppState::WaveTransition);
+}
+
+// Switches to the playfield of the coming level at the start of the transition, moving everything left into the new bounds
+fn resize_playfield (
+    state: Res<State<AppState>>,
+    wave: Res<Wave>,
+    levels: Res<Levels>,
+    mut playfield: ResMut<Playfield>,
+    mut query: Query<&mut Transform, With<Original>>,
+) {
+    if *state.get() != AppState::WaveTransition {
+        return;
+    }
+    let level_playfield = levels.get(wave.number).playfield();
+    if *playfield == level_playfield {
+        return;
+    }
+    *playfield = level_playfield;
+    for mut transform in query.iter_mut() {
+        let position = playfield.torus().wrap(transform.translation.truncate());
+        transform.translation.x = position.x;
+        transform.translation.y = position.y;
+    }
+}
+
+// A ship that an asteroid of the next wave can land on
+type ShipInTheWay = (Entity, &'static Transform, &'static CollisionType, Option<&'static Invulnerable>);
+
+// Waits out the transition between waves, with the ship still flying around, then spawns the next field
+#[allow(clippy::too_many_arguments)]
+fn advance_wave (
+    mut commands: Commands,
+    mut wave: ResMut<Wave>,
+    mut game_rng: ResMut<GameRng>,
+    mut next_state: ResMut<NextState<AppState>>,
+    state: Res<State<AppState>>,
+    sim_tick: Res<SimTick>,
+    levels: Res<Levels>,
+    playfield: Res<Playfield>,
+    query_asteroids: Query<(), With<AsteroidSize>>,
+    query_free_space: Query<&Transform, With<CollisionType>>,
+    query_ships: Query<ShipInTheWay, With<Player>>,
+    mut query_ship_stats: Query<&mut ShipStats, With<Player>>,
+) {
+    match state.get() {
+        AppState::InGame if wave.in_progress && query_asteroids.is_empty() => {
+            wave.number += 1;
+            wave.in_progress = false;
+            wave.next_wave_at = sim_tick.0 + ticks_from_seconds(WAVE_TRANSITION_SECONDS);
+            next_state.set(AppState::WaveTransition);
+        }
+        AppState::WaveTransition if sim_tick.0 >= wave.next_wave_at => {
+            let mut positions: Vec<Vec2> = query_free_space.iter().map(|transform| transform.translation.truncate()).collect();
+            let level = levels.get(wave.number);
+            let mut shielded = Vec::<Entity>::new();
+            for placement in level.asteroids.iter() {
+                let position = playfield.torus().wrap(Vec2::new(placement.position.0, placement.position.1));
+                positions.push(position);
+                shield_ships_near(&mut commands, &playfield.torus(), sim_tick.0, position, &query_ships, &mut shielded);
+                let mut asteroid = spawn_asteroid(&mut commands, &mut game_rng.gameplay, placement.size);
+                asteroid
+                .insert(Transform::from_translation(position.extend(AsteroidBigBundle::default().physics_object.transform.translation.z)))
+                .insert(Velocity { x: placement.velocity.0, y: placement.velocity.1 });
+                if placement.kind == AsteroidKind::Dense {
+                    asteroid.insert(Mass(asteroid_mass(placement.size) * DENSE_ASTEROID_MASS_FACTOR));
+                }
+            }
+            for group in level.random_asteroids.iter() {
+                for _i in 0..group.count {
+                    let position = random_free_position(&playfield, &mut game_rng.gameplay, &positions)
+                    .unwrap_or_else(|| {
+                        // The playfield is too crowded for a free spot, so the asteroid goes anywhere
+                        let position = random_position(&playfield, &mut game_rng.gameplay);
+                        shield_ships_near(&mut commands, &playfield.torus(), sim_tick.0, position, &query_ships, &mut shielded);
+                        position
+                    });
+                    positions.push(position);
+                    let direction = rf32(&mut game_rng.gameplay, 0.0, 2.0 * PI);
+                    let speed = rf32(&mut game_rng.gameplay, level.speed.0, level.speed.1);
+                    let mut asteroid = spawn_asteroid(&mut commands, &mut game_rng.gameplay, group.size);
+                    asteroid
+                    .insert(Transform::from_translation(position.extend(AsteroidBigBundle::default().physics_object.transform.translation.z)))
+                    .insert(Velocity { x: speed * direction.cos(), y: speed * direction.sin() });
+                    if group.kind == AsteroidKind::Dense {
+                        asteroid.insert(Mass(asteroid_mass(group.size) * DENSE_ASTEROID_MASS_FACTOR));
+                    }
+                }
+            }
+            for mut ship_stats in query_ship_stats.iter_mut() {
+                level.ship_stats.apply(&mut ship_stats);
+            }
+            wave.in_progress = true;
+            next_state.set(AppState::InGame);
+        }
+        _ => {}
+    }
+}
+
+// The ships stay where they were through the transition, so an asteroid can land on one.
+// That ship is shielded for a moment, like after a respawn.
+fn shield_ships_near(
+    commands: &mut Commands,
+    torus: &Torus,
+    sim_tick: u64,
+    position: Vec2,
+    query_ships: &Query<ShipInTheWay, With<Player>>,
+    shielded: &mut Vec<Entity>,
+) {
+    for (ship, transform, collision_type, invulnerable) in query_ships.iter() {
+        if shielded.contains(&ship) || torus.distance(transform.translation.truncate(), position) >= SPAWN_CLEARANCE {
+            continue;
+        }
+        shielded.push(ship);
+        shield_for_a_moment(&mut commands.entity(ship), sim_tick, invulnerable, *collision_type);
+    }
+}
+
+fn spawn_asteroid<'w, 's, 'a>(commands: &'a mut Commands<'w, 's>, rng: &mut impl Rng, size: AsteroidSize) -> EntityCommands<'w, 's, 'a> {
+    let (mut asteroid, radius) = match size {
+        AsteroidSize::Big => (commands.spawn(AsteroidBigBundle::default()), AsteroidBigBundle::default().physics_object.radius.0),
+        AsteroidSize::Medium => (commands.spawn(AsteroidMediumBundle::default()), AsteroidMediumBundle::default().physics_object.radius.0),
+        AsteroidSize::Small => (commands.spawn(AsteroidSmallBundle::default()), AsteroidSmallBundle::default().physics_object.radius.0),
+    };
+    asteroid
+    .insert(random_rotation(rng))
+    .insert(ConvexHull::random(rng, radius));
+    asteroid
+}
+
+fn asteroid_mass(size: AsteroidSize) -> f32 {
+    match size {
+        AsteroidSize::Big => AsteroidBigBundle::default().physics_object.mass.0,
+        AsteroidSize::Medium => AsteroidMediumBundle::default().physics_object.mass.0,
+        AsteroidSize::Small => AsteroidSmallBundle::default().physics_object.mass.0,
+    }
+}
\ No newline at end of file
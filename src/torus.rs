@@ -0,0 +1,320 @@
+use bevy::prelude::*;
+
+// Geometry on the playfield, where leaving one edge brings you back in from the opposite one.
+// The world origin is in the middle, so positions inside the field go from -size / 2 to size / 2.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Torus {
+    pub size: Vec2,
+}
+
+impl Torus {
+    pub fn new(size: Vec2) -> Self {
+        Self { size }
+    }
+
+    // Moves a position that has left the field back in from the opposite edge, however far out it is
+    pub fn wrap(&self, position: Vec2) -> Vec2 {
+        Vec2::new(
+            wrap_axis(position.x, self.size.x),
+            wrap_axis(position.y, self.size.y),
+        )
+    }
+
+    // Shortest vector from one position to another, going over the edges when that is shorter
+    pub fn delta(&self, from: Vec2, to: Vec2) -> Vec2 {
+        let difference = to - from;
+        Vec2::new(
+            wrap_axis(difference.x, self.size.x),
+            wrap_axis(difference.y, self.size.y),
+        )
+    }
+
+    pub fn distance(&self, from: Vec2, to: Vec2) -> f32 {
+        self.delta(from, to).length()
+    }
+
+    // The copy of a position closest to another, which can be outside the field
+    pub fn closest(&self, from: Vec2, to: Vec2) -> Vec2 {
+        from + self.delta(from, to)
+    }
+
+    // Distance along a ray to where it first hits a circle, or None if it doesn't within max_distance.
+    // The ray keeps going over the edges, so it can hit any copy of the circle, also after looping around.
+    pub fn raycast(&self, origin: Vec2, direction: Vec2, max_distance: f32, center: Vec2, radius: f32) -> Option<f32> {
+        let direction = direction.normalize_or_zero();
+        if direction == Vec2::ZERO {
+            return None;
+        }
+        let end = origin + direction * max_distance;
+        let closest_center = self.closest(origin, center);
+        self.copies_in(closest_center, origin.min(end) - radius, origin.max(end) + radius)
+        .filter_map(|copy| ray_circle(origin, direction, copy, radius))
+        .filter(|distance| *distance <= max_distance)
+        .min_by(f32::total_cmp)
+    }
+
+    // Where two segments cross, given as start points and the vectors to their ends. Segments can cross the edges.
+    // Returns the crossing closest to the start of the first segment, wrapped into the field.
+    pub fn segment_intersection(&self, start_1: Vec2, vector_1: Vec2, start_2: Vec2, vector_2: Vec2) -> Option<Vec2> {
+        let end_1 = start_1 + vector_1;
+        // Every copy of the second segment whose bounds overlap the bounds of the first one
+        let closest_start_2 = self.closest(start_1, start_2);
+        let bounds_min = start_1.min(end_1) - vector_2.max(Vec2::ZERO);
+        let bounds_max = start_1.max(end_1) - vector_2.min(Vec2::ZERO);
+        self.copies_in(closest_start_2, bounds_min, bounds_max)
+        .filter_map(|copy| segment_segment(start_1, vector_1, copy, vector_2))
+        .min_by(f32::total_cmp)
+        .map(|t| self.wrap(start_1 + vector_1 * t))
+    }
+
+    // Whether two axis aligned boxes overlap, given by their centers and half their width and height
+    pub fn aabb_overlap(&self, center_1: Vec2, half_size_1: Vec2, center_2: Vec2, half_size_2: Vec2) -> bool {
+        let delta = self.delta(center_1, center_2).abs();
+        let reach = half_size_1 + half_size_2;
+        // Boxes as wide as the field overlap everything along that axis
+        (delta.x < reach.x || reach.x * 2.0 >= self.size.x) && (delta.y < reach.y || reach.y * 2.0 >= self.size.y)
+    }
+
+    // Offsets of the copies of a box that show inside the field. Always includes zero for a box centered in the field,
+    // plus one copy per edge or corner the box crosses.
+    pub fn aabb_copies(&self, center: Vec2, half_size: Vec2) -> Vec<Vec2> {
+        let center = self.wrap(center);
+        let half_field = self.size / 2.0;
+        let offsets = |position: f32, half_size: f32, half_field: f32, field: f32| {
+            let mut offsets = vec![0.0];
+            if position - half_size < -half_field { offsets.push(field); }
+            if position + half_size > half_field { offsets.push(-field); }
+            offsets
+        };
+        let x_offsets = offsets(center.x, half_size.x, half_field.x, self.size.x);
+        let y_offsets = offsets(center.y, half_size.y, half_field.y, self.size.y);
+        y_offsets.iter().flat_map(|y| x_offsets.iter().map(move |x| Vec2::new(*x, *y))).collect()
+    }
+
+    // Every copy of a position, one field size apart, that is inside the given bounds
+    fn copies_in(&self, position: Vec2, bounds_min: Vec2, bounds_max: Vec2) -> impl Iterator<Item = Vec2> {
+        let size = self.size;
+        let first = ((bounds_min - position) / size).ceil();
+        let last = ((bounds_max - position) / size).floor();
+        let (x_range, y_range) = (first.x as i32..=last.x as i32, first.y as i32..=last.y as i32);
+        y_range.flat_map(move |y| x_range.clone().map(move |x| position + Vec2::new(x as f32, y as f32) * size))
+    }
+}
+
+// Coordinate moved into -size / 2 to size / 2
+fn wrap_axis(coordinate: f32, size: f32) -> f32 {
+    let wrapped = (coordinate + size / 2.0).rem_euclid(size) - size / 2.0;
+    // rem_euclid can round up to exactly size for tiny negative values
+    if wrapped >= size / 2.0 { wrapped - size } else { wrapped }
+}
+
+// Distance along a ray with a normalized direction to where it enters a circle, zero if it starts inside
+fn ray_circle(origin: Vec2, direction: Vec2, center: Vec2, radius: f32) -> Option<f32> {
+    let to_center = center - origin;
+    if to_center.length_squared() <= radius * radius {
+        return Some(0.0);
+    }
+    let along = to_center.dot(direction);
+    let closest_squared = to_center.length_squared() - along * along;
+    if along < 0.0 || closest_squared > radius * radius {
+        return None;
+    }
+    Some(along - (radius * radius - closest_squared).sqrt())
+}
+
+// Fraction along the first segment where it crosses the second one. Parallel segments never cross.
+fn segment_segment(start_1: Vec2, vector_1: Vec2, start_2: Vec2, vector_2: Vec2) -> Option<f32> {
+    let denominator = vector_1.perp_dot(vector_2);
+    if denominator.abs() < f32::EPSILON {
+        return None;
+    }
+    let between = start_2 - start_1;
+    let t = between.perp_dot(vector_2) / denominator;
+    let u = between.perp_dot(vector_1) / denominator;
+    ((0.0..=1.0).contains(&t) && (0.0..=1.0).contains(&u)).then_some(t)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    const TOLERANCE: f32 = 1e-2;
+
+    fn torus() -> impl Strategy<Value = Torus> {
+        (200.0f32..2000.0, 200.0f32..2000.0).prop_map(|(width, height)| Torus::new(Vec2::new(width, height)))
+    }
+
+    fn any_position() -> impl Strategy<Value = Vec2> {
+        (-5000.0f32..5000.0, -5000.0f32..5000.0).prop_map(|(x, y)| Vec2::new(x, y))
+    }
+
+    fn inside(torus: &Torus, position: Vec2) -> bool {
+        let half = torus.size / 2.0;
+        position.x >= -half.x && position.x < half.x && position.y >= -half.y && position.y < half.y
+    }
+
+    // Same position on the torus, also when one of them is just across an edge
+    fn same_place(torus: &Torus, position_1: Vec2, position_2: Vec2) -> bool {
+        torus.distance(position_1, position_2) < TOLERANCE
+    }
+
+    // Reference raycast checking a fixed block of copies around the ray
+    fn brute_force_raycast(torus: &Torus, origin: Vec2, direction: Vec2, max_distance: f32, center: Vec2, radius: f32) -> Option<f32> {
+        let direction = direction.normalize();
+        let mut hits = Vec::new();
+        for x in -4..=4 {
+            for y in -4..=4 {
+                let copy = torus.wrap(center) + Vec2::new(x as f32, y as f32) * torus.size;
+                if let Some(distance) = ray_circle(torus.wrap(origin), direction, copy, radius) {
+                    hits.push(distance);
+                }
+            }
+        }
+        hits.into_iter().filter(|distance| *distance <= max_distance).min_by(f32::total_cmp)
+    }
+
+    // Distance from a position inside the field to the closest copy of a segment
+    fn distance_to_segment(torus: &Torus, position: Vec2, start: Vec2, vector: Vec2) -> f32 {
+        let mut distances = Vec::new();
+        for x in -2..=2 {
+            for y in -2..=2 {
+                let copy = torus.wrap(start) + Vec2::new(x as f32, y as f32) * torus.size;
+                let t = ((position - copy).dot(vector) / vector.length_squared()).clamp(0.0, 1.0);
+                distances.push(position.distance(copy + vector * t));
+            }
+        }
+        distances.into_iter().min_by(f32::total_cmp).unwrap()
+    }
+
+    #[test]
+    fn delta_wraps_the_vertical_axis_by_the_height() {
+        // closest_position used to wrap y by the width, which only showed on fields that aren't square
+        let torus = Torus::new(Vec2::new(1280.0, 720.0));
+        let from = Vec2::new(0.0, 350.0);
+        let to = Vec2::new(0.0, -350.0);
+        assert_eq!(torus.delta(from, to), Vec2::new(0.0, 20.0));
+        assert_eq!(torus.closest(from, to), Vec2::new(0.0, 370.0));
+    }
+
+    #[test]
+    fn raycast_hits_across_the_edge() {
+        let torus = Torus::new(Vec2::new(1280.0, 720.0));
+        let hit = torus.raycast(Vec2::new(600.0, 0.0), Vec2::X, 500.0, Vec2::new(-600.0, 0.0), 20.0);
+        assert!((hit.unwrap() - 60.0).abs() < TOLERANCE);
+        assert_eq!(torus.raycast(Vec2::new(600.0, 0.0), -Vec2::X, 500.0, Vec2::new(-600.0, 0.0), 20.0), None);
+    }
+
+    proptest! {
+        #[test]
+        fn wrap_moves_positions_inside(torus in torus(), position in any_position()) {
+            let wrapped = torus.wrap(position);
+            prop_assert!(inside(&torus, wrapped), "{:?} wrapped to {:?}", position, wrapped);
+            prop_assert!(same_place(&torus, wrapped, position));
+            prop_assert_eq!(torus.wrap(wrapped), wrapped);
+        }
+
+        #[test]
+        fn delta_is_the_shortest_way(torus in torus(), from in any_position(), to in any_position()) {
+            let delta = torus.delta(from, to);
+            prop_assert!(delta.x.abs() <= torus.size.x / 2.0 && delta.y.abs() <= torus.size.y / 2.0);
+            prop_assert!(same_place(&torus, from + delta, to));
+            // Going the other way around is never shorter
+            for offset in [Vec2::new(torus.size.x, 0.0), Vec2::new(0.0, torus.size.y)] {
+                prop_assert!(delta.length() <= (delta + offset).length() + TOLERANCE);
+                prop_assert!(delta.length() <= (delta - offset).length() + TOLERANCE);
+            }
+        }
+
+        #[test]
+        fn distance_is_symmetric(torus in torus(), position_1 in any_position(), position_2 in any_position()) {
+            let distance = torus.distance(position_1, position_2);
+            prop_assert!((distance - torus.distance(position_2, position_1)).abs() < TOLERANCE);
+            prop_assert!(distance <= torus.size.length() / 2.0 + TOLERANCE);
+            prop_assert!((torus.distance(position_1, torus.closest(position_1, position_2)) - distance).abs() < TOLERANCE);
+        }
+
+        #[test]
+        fn raycast_finds_the_closest_copy(
+            torus in torus(), origin in any_position(), angle in 0.0f32..6.3, range in 0.0f32..1.0,
+            center in any_position(), radius in 1.0f32..100.0,
+        ) {
+            // Up to three times around the field, which the reference covers
+            let max_distance = range * 3.0 * torus.size.min_element();
+            let direction = Vec2::from_angle(angle);
+            let hit = torus.raycast(origin, direction, max_distance, center, radius);
+            let expected = brute_force_raycast(&torus, origin, direction, max_distance, center, radius);
+            match (hit, expected) {
+                (Some(hit), Some(expected)) => prop_assert!((hit - expected).abs() < 0.1, "{} != {}", hit, expected),
+                _ => prop_assert_eq!(hit, expected),
+            }
+            if let Some(hit) = hit {
+                // The hit point is on the edge of the circle, or the ray started inside it
+                let gap = torus.distance(origin + direction * hit, center);
+                prop_assert!(hit == 0.0 || (gap - radius).abs() < 0.1);
+            }
+        }
+
+        #[test]
+        fn segment_intersection_is_on_both_segments(
+            torus in torus(), start_1 in any_position(), vector_1 in any_position(), start_2 in any_position(), vector_2 in any_position(),
+        ) {
+            let vector_1 = vector_1.clamp_length_max(torus.size.min_element());
+            let vector_2 = vector_2.clamp_length_max(torus.size.min_element());
+            if let Some(crossing) = torus.segment_intersection(start_1, vector_1, start_2, vector_2) {
+                prop_assert!(inside(&torus, crossing));
+                for (start, vector) in [(start_1, vector_1), (start_2, vector_2)] {
+                    prop_assert!(distance_to_segment(&torus, crossing, start, vector) < 0.5);
+                }
+            }
+        }
+
+        #[test]
+        fn segments_crossing_an_edge_intersect(torus in torus(), y in -100.0f32..100.0, length in 20.0f32..100.0) {
+            // A horizontal segment crossing the right edge, and a vertical one just inside the left edge
+            let half = torus.size / 2.0;
+            let horizontal_start = Vec2::new(half.x - length / 2.0, y);
+            let vertical_start = Vec2::new(-half.x + length / 4.0, y - length / 2.0);
+            let crossing = torus.segment_intersection(horizontal_start, Vec2::new(length, 0.0), vertical_start, Vec2::new(0.0, length));
+            prop_assert!(crossing.is_some());
+            prop_assert!(same_place(&torus, crossing.unwrap(), Vec2::new(vertical_start.x, y)));
+        }
+
+        #[test]
+        fn aabb_overlap_matches_the_copies(
+            torus in torus(), center_1 in any_position(), center_2 in any_position(),
+            half_1 in (1.0f32..300.0, 1.0f32..300.0), half_2 in (1.0f32..300.0, 1.0f32..300.0),
+        ) {
+            let half_1 = Vec2::new(half_1.0, half_1.1).min(torus.size / 2.0);
+            let half_2 = Vec2::new(half_2.0, half_2.1).min(torus.size / 2.0);
+            // Overlap found by checking the copies of both boxes inside the field
+            let brute_force = torus.aabb_copies(center_1, half_1).iter().any(|offset_1| {
+                torus.aabb_copies(center_2, half_2).iter().any(|offset_2| {
+                    let delta = (torus.wrap(center_2) + *offset_2 - torus.wrap(center_1) - *offset_1).abs();
+                    delta.x < half_1.x + half_2.x && delta.y < half_1.y + half_2.y
+                })
+            });
+            prop_assert_eq!(torus.aabb_overlap(center_1, half_1, center_2, half_2), brute_force);
+        }
+
+        #[test]
+        fn aabb_copies_cover_the_box(torus in torus(), center in any_position(), half in (1.0f32..300.0, 1.0f32..300.0), point in (0.0f32..1.0, 0.0f32..1.0)) {
+            let half = Vec2::new(half.0, half.1).min(torus.size / 2.0);
+            let copies = torus.aabb_copies(center, half);
+            let wrapped_center = torus.wrap(center);
+            // Any point of the box, wrapped into the field, is inside one of the copies
+            let point_in_box = wrapped_center - half + Vec2::new(point.0, point.1) * half * 2.0;
+            let wrapped_point = torus.wrap(point_in_box);
+            let covered = copies.iter().any(|offset| {
+                let delta = (wrapped_point - (wrapped_center + *offset)).abs();
+                delta.x <= half.x + TOLERANCE && delta.y <= half.y + TOLERANCE
+            });
+            prop_assert!(covered);
+            // Every copy shows inside the field
+            for offset in copies.iter() {
+                let delta = (wrapped_center + *offset).abs();
+                prop_assert!(delta.x < torus.size.x / 2.0 + half.x && delta.y < torus.size.y / 2.0 + half.y);
+            }
+        }
+    }
+}
\ No newline at end of file
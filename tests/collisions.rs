@@ -0,0 +1,128 @@
+mod common;
+
+use bevy::prelude::*;
+use cometbuster::{
+    c_bundles::{AsteroidBigBundle, AsteroidSmallBundle, BulletBundle},
+    c_chargelevel::ChargeLevel,
+    c_events::EvCollision,
+    c_movement_and_collisions::{ConvexHull, Velocity},
+    c_sprites::AsteroidSize,
+    c_tags::Bullet,
+    s_simulation::SimulationSet,
+};
+use common::*;
+
+// Every EvCollision published by collision detection so far
+#[derive(Resource, Default)]
+struct PublishedCollisions(Vec<EvCollision>);
+
+fn collect_collisions(
+    mut published: ResMut<PublishedCollisions>,
+    mut collision_reader: EventReader<EvCollision>,
+) {
+    published.0.extend(collision_reader.read().copied());
+}
+
+// A bullet flying right, towards a big asteroid sitting still at the origin
+fn spawn_bullet_and_big_asteroid(app: &mut App, charge_level: f32) -> (Entity, Entity) {
+    let asteroid = app.world.spawn(AsteroidBigBundle::default())
+    .insert(Transform::from_xyz(0.0, 0.0, 10.0))
+    .id();
+    let bullet = app.world.spawn(BulletBundle::default())
+    .insert(Transform::from_xyz(-120.0, 0.0, 10.0))
+    .insert(Velocity { x: 400.0, y: 0.0 })
+    .insert(ChargeLevel(charge_level))
+    .id();
+    (bullet, asteroid)
+}
+
+fn asteroid_sizes(app: &mut App) -> Vec<AsteroidSize> {
+    let mut query = app.world.query::<&AsteroidSize>();
+    query.iter(&app.world).copied().collect()
+}
+
+#[test]
+fn charged_bullet_splits_big_asteroid_into_three_mediums() {
+    let mut app = headless_app(0);
+    start_empty_game(&mut app);
+    spawn_bullet_and_big_asteroid(&mut app, 2.0);
+
+    step_ticks(&mut app, 15);
+
+    let sizes = asteroid_sizes(&mut app);
+    assert_eq!(sizes.len(), 3, "expected three fragments, found {:?}", sizes);
+    assert!(sizes.iter().all(|size| *size == AsteroidSize::Medium), "expected only mediums, found {:?}", sizes);
+    let mut query_hulls = app.world.query::<&ConvexHull>();
+    assert_eq!(query_hulls.iter(&app.world).count(), 3, "every fragment should get a hull");
+    let mut query_bullets = app.world.query_filtered::<(), With<Bullet>>();
+    assert_eq!(query_bullets.iter(&app.world).count(), 0, "the bullet should be destroyed");
+}
+
+#[test]
+fn weak_bullet_bounces_off_big_asteroid() {
+    let mut app = headless_app(0);
+    start_empty_game(&mut app);
+    let (bullet, asteroid) = spawn_bullet_and_big_asteroid(&mut app, 0.0);
+
+    step_ticks(&mut app, 15);
+
+    assert_eq!(asteroid_sizes(&mut app), vec![AsteroidSize::Big]);
+    let bullet_velocity = app.world.get::<Velocity>(bullet).expect("the bullet should survive the bounce");
+    assert!(bullet_velocity.x < 0.0, "the bullet should fly back, its velocity is {:?}", bullet_velocity);
+    let asteroid_velocity = app.world.get::<Velocity>(asteroid).unwrap();
+    assert!(asteroid_velocity.x > 0.0, "the asteroid should be pushed away, its velocity is {:?}", asteroid_velocity);
+}
+
+#[test]
+fn bullet_passes_by_the_corner_of_a_hull() {
+    let mut app = headless_app(0);
+    start_empty_game(&mut app);
+    // A square hull inside the radius of the asteroid, the bullet flies through the gap past its corner
+    let asteroid = app.world.spawn(AsteroidBigBundle::default())
+    .insert(Transform::from_xyz(0.0, 0.0, 10.0))
+    .insert(ConvexHull(vec![Vec2::new(-50.0, -50.0), Vec2::new(50.0, -50.0), Vec2::new(50.0, 50.0), Vec2::new(-50.0, 50.0)]))
+    .id();
+    let bullet = app.world.spawn(BulletBundle::default())
+    .insert(Transform::from_xyz(-120.0, 75.0, 10.0))
+    .insert(Velocity { x: 400.0, y: 0.0 })
+    .insert(ChargeLevel(0.0))
+    .id();
+
+    step_ticks(&mut app, 15);
+
+    assert_eq!(app.world.get::<Velocity>(bullet).unwrap().x, 400.0, "the bullet should not bounce off the bounding circle");
+    assert_eq!(app.world.get::<Velocity>(asteroid).unwrap().x, 0.0);
+}
+
+#[test]
+fn detection_publishes_the_normal_contact_point_and_impulse() {
+    let mut app = headless_app(0);
+    app
+    .init_resource::<PublishedCollisions>()
+    .add_systems(FixedUpdate, collect_collisions.in_set(SimulationSet::Response))
+    ;
+    start_empty_game(&mut app);
+    // A small asteroid at rest, and one moving into it from the right. After one tick of movement they overlap by 6.
+    let resting = app.world.spawn(AsteroidSmallBundle::default())
+    .insert(Transform::from_xyz(0.0, 0.0, 10.0))
+    .id();
+    let moving = app.world.spawn(AsteroidSmallBundle::default())
+    .insert(Transform::from_xyz(30.0, 0.0, 10.0))
+    .insert(Velocity { x: -120.0, y: 0.0 })
+    .id();
+
+    step_ticks(&mut app, 1);
+
+    let collisions = &app.world.resource::<PublishedCollisions>().0;
+    assert_eq!(collisions.len(), 1, "expected one collision");
+    let collision = collisions[0];
+    // Either body can be A. The normal points from A to B, and the contact point is on the surface of A.
+    let position = |entity: Entity| if entity == resting { Vec2::ZERO } else { Vec2::new(28.0, 0.0) };
+    assert!([(resting, moving), (moving, resting)].contains(&(collision.a, collision.b)));
+    let normal = (position(collision.b) - position(collision.a)).normalize();
+    assert!(collision.normal.abs_diff_eq(normal, 1e-4), "the normal is {:?}", collision.normal);
+    assert!(collision.contact_point.abs_diff_eq(position(collision.a) + normal * 17.0, 1e-3), "the contact point is {:?}", collision.contact_point);
+    assert!((collision.penetration - 6.0).abs() < 1e-3, "the penetration is {}", collision.penetration);
+    // Perfectly elastic between two masses of 20 closing at 120: twice the reduced mass of 10, times 120, along the normal
+    assert!(collision.impulse.abs_diff_eq(normal * 2400.0, 1e-2), "the impulse is {:?}", collision.impulse);
+}
\ No newline at end of file
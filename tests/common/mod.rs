@@ -0,0 +1,108 @@
+// Shared by the integration tests. Not every test file uses every helper.
+#![allow(dead_code)]
+
+use bevy::{
+    prelude::*,
+    app::Plugins,
+    time::TimeUpdateStrategy,
+};
+use std::time::Duration;
+
+use cometbuster::{
+    consts::*,
+    c_appstate::AppState,
+    c_bundles::ShipBundle,
+    c_controls::{ControlFrame, ControlState},
+    c_gamerng::GameRng,
+    c_lifetime_spawntime::SimTick,
+    c_movement_and_collisions::Velocity,
+    c_shipstats::Energy,
+    s_gameplay::GameplayPlugin,
+};
+
+pub type EntityState = (Entity, Transform, Option<(f32, f32)>, Option<f32>);
+
+// A headless App running the gameplay plugins, where every update advances time by exactly one tick
+pub fn headless_app(seed: u64) -> App {
+    headless_app_with(seed, GameplayPlugin)
+}
+
+// The same with other plugins in place of the gameplay plugins alone
+pub fn headless_app_with<M>(seed: u64, plugins: impl Plugins<M>) -> App {
+    let mut app = App::new();
+    app
+    .add_plugins(MinimalPlugins)
+    .insert_resource(TimeUpdateStrategy::ManualDuration(Duration::from_secs_f64(1.0 / TICKS_PER_SECOND as f64)))
+    .insert_resource(GameRng::from_seed(seed))
+    .add_plugins(plugins)
+    ;
+    app.finish();
+    app.cleanup();
+    app
+}
+
+// Starts the game from the normal start state, with the ship and asteroids spawned from the seed
+pub fn start_game(app: &mut App) {
+    app.world.resource_mut::<NextState<AppState>>().set(AppState::SpawnStart);
+    while *app.world.resource::<State<AppState>>().get() != AppState::InGame {
+        app.update();
+    }
+}
+
+// Starts the game with an empty world, for tests that spawn their own entities.
+// Includes a ship parked in a corner, as a new one is spawned at a random position whenever there is none.
+pub fn start_empty_game(app: &mut App) -> Entity {
+    let ship = app.world.spawn(ShipBundle::default())
+    .insert(Transform::from_xyz(-PLAYFIELD_WIDTH / 2.0 + 50.0, -PLAYFIELD_HEIGHT / 2.0 + 50.0, 20.0))
+    .id();
+    app.world.resource_mut::<NextState<AppState>>().set(AppState::InGame);
+    app.update();
+    ship
+}
+
+// Runs updates until the simulation has advanced the given number of ticks
+pub fn step_ticks(app: &mut App, ticks: u64) {
+    let target = app.world.resource::<SimTick>().0 + ticks;
+    while app.world.resource::<SimTick>().0 < target {
+        app.update();
+    }
+}
+
+// The same scripted controls on every tick, turning, thrusting, firing and raising the shield now and then
+pub fn scripted_frame(tick: u64) -> ControlFrame {
+    ControlFrame {
+        accelerate: tick % 90 < 50,
+        turn_left: tick % 40 < 10,
+        turn_right: tick % 70 > 60,
+        fire: tick % 15 < 3,
+        shield: tick % 200 > 185,
+        steer: None,
+    }
+}
+
+// Gives every ship the scripted controls of the next tick
+pub fn advance_scripted_controls(app: &mut App) {
+    let tick = app.world.resource::<SimTick>().0;
+    let mut query = app.world.query::<&mut ControlState>();
+    for mut control_state in query.iter_mut(&mut app.world) {
+        control_state.advance(scripted_frame(tick));
+    }
+}
+
+// Runs the given number of ticks with the scripted controls
+pub fn step_scripted_ticks(app: &mut App, ticks: u64) {
+    for _i in 0..ticks {
+        advance_scripted_controls(app);
+        step_ticks(app, 1);
+    }
+}
+
+// Every Transform, with the Velocity and Energy of the entities that have them, for comparing two runs
+pub fn world_state(app: &mut App) -> Vec<EntityState> {
+    let mut query = app.world.query::<(Entity, &Transform, Option<&Velocity>, Option<&Energy>)>();
+    let mut state: Vec<EntityState> = query.iter(&app.world)
+    .map(|(entity, transform, velocity, energy)| (entity, *transform, velocity.map(|velocity| (velocity.x, velocity.y)), energy.map(|energy| energy.0)))
+    .collect();
+    state.sort_by_key(|(entity, ..)| *entity);
+    state
+}
\ No newline at end of file
@@ -0,0 +1,74 @@
+mod common;
+
+use bevy::prelude::*;
+use cometbuster::{
+    consts::*,
+    c_controls::{Action, Binding, ControlFrame, ControlState, Controls, Stick},
+    c_movement_and_collisions::Angle,
+};
+use common::*;
+
+fn devices() -> (Input<KeyCode>, Input<GamepadButton>, Axis<GamepadAxis>) {
+    (Input::default(), Input::default(), Axis::default())
+}
+
+#[test]
+fn keys_buttons_and_axes_trigger_their_actions() {
+    let controls = Controls {
+        bindings: vec![
+            (Action::Accelerate, Binding::Axis(GamepadAxisType::LeftStickY, 1.0)),
+            (Action::Fire, Binding::Key(KeyCode::X)),
+            (Action::Shield, Binding::Button(GamepadButtonType::East)),
+        ],
+        steering: None,
+        gamepad: 1,
+    };
+    let gamepad = Gamepad::new(1);
+    let (mut keyboard, mut buttons, mut axes) = devices();
+    keyboard.press(KeyCode::X);
+    buttons.press(GamepadButton::new(gamepad, GamepadButtonType::East));
+    axes.set(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY), 0.8);
+
+    let frame = controls.frame(&keyboard, &buttons, &axes);
+    for action in Action::ALL {
+        let bound = matches!(action, Action::Accelerate | Action::Fire | Action::Shield);
+        assert_eq!(frame.pressed(action), bound, "{:?}", action);
+    }
+
+    // The buttons of another gamepad, and a stick barely moved, do nothing
+    let (keyboard, mut buttons, mut axes) = devices();
+    buttons.press(GamepadButton::new(Gamepad::new(0), GamepadButtonType::East));
+    axes.set(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY), GAMEPAD_DEAD_ZONE / 2.0);
+    assert_eq!(controls.frame(&keyboard, &buttons, &axes), ControlFrame::default());
+}
+
+#[test]
+fn steering_stick_points_the_way_outside_the_dead_zone() {
+    let controls = Controls { steering: Some(Stick::Left), ..Controls::default() };
+    let gamepad = Gamepad::new(controls.gamepad);
+    let (keyboard, buttons, mut axes) = devices();
+    axes.set(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickX), 0.0);
+    axes.set(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY), -GAMEPAD_DEAD_ZONE / 2.0);
+    assert_eq!(controls.frame(&keyboard, &buttons, &axes).steer, None);
+
+    axes.set(GamepadAxis::new(gamepad, GamepadAxisType::LeftStickY), -1.0);
+    let steer = controls.frame(&keyboard, &buttons, &axes).steer.expect("a stick pushed down should steer");
+    assert!((steer + PI / 2.0).abs() < 1e-5, "{}", steer);
+}
+
+#[test]
+fn ship_turns_to_face_the_steering_direction() {
+    let mut app = headless_app(0);
+    let ship = start_empty_game(&mut app);
+    app.world.get_mut::<Angle>(ship).unwrap().0 = 0.0;
+    let target = 3.0 * PI / 4.0;
+
+    // Turning half way round at 4 radians per second takes well under a second, then the ship holds still
+    for _i in 0..TICKS_PER_SECOND {
+        app.world.get_mut::<ControlState>(ship).unwrap().advance(ControlFrame { steer: Some(target), ..Default::default() });
+        step_ticks(&mut app, 1);
+    }
+
+    let angle = app.world.get::<Angle>(ship).unwrap().0;
+    assert!((angle - target).abs() < 1e-4, "{}", angle);
+}
\ No newline at end of file
@@ -0,0 +1,31 @@
+mod common;
+
+use bevy::prelude::*;
+use cometbuster::c_lifetime_spawntime::SimTick;
+use common::*;
+
+fn run_scripted_game(seed: u64, ticks: u64) -> App {
+    let mut app = headless_app(seed);
+    start_game(&mut app);
+    step_scripted_ticks(&mut app, ticks);
+    app
+}
+
+#[test]
+fn same_seed_and_inputs_give_identical_world_state() {
+    let mut app_1 = run_scripted_game(7, 900);
+    let mut app_2 = run_scripted_game(7, 900);
+
+    assert_eq!(app_1.world.resource::<SimTick>().0, app_2.world.resource::<SimTick>().0);
+    let state_1 = world_state(&mut app_1);
+    let state_2 = world_state(&mut app_2);
+    assert_eq!(state_1.len(), state_2.len(), "the runs should end with the same entities");
+    // Compared exactly, not within a tolerance
+    for (entity_state_1, entity_state_2) in state_1.iter().zip(state_2.iter()) {
+        assert!(entity_state_1 == entity_state_2, "the runs went apart: {:?} and {:?}", entity_state_1, entity_state_2);
+    }
+
+    // Another seed plays out differently, so the comparison above has something to catch
+    let mut app_3 = run_scripted_game(8, 900);
+    assert!(world_state(&mut app_3) != state_1);
+}
\ No newline at end of file
@@ -0,0 +1,51 @@
+mod common;
+
+use cometbuster::{
+    consts::*,
+    c_controls::{ControlFrame, ControlState},
+    c_movement_and_collisions::CollisionType,
+    c_shipstats::{Energy, ShipStats},
+};
+use common::*;
+
+#[test]
+fn shield_drains_energy_at_the_expected_rate() {
+    let mut app = headless_app(0);
+    let ship = start_empty_game(&mut app);
+    // Without regeneration, the energy only changes from the drain
+    app.world.get_mut::<ShipStats>(ship).unwrap().shield_regeneration = 0.0;
+
+    let hold_shield = |app: &mut bevy::app::App, ticks: u64| {
+        for _i in 0..ticks {
+            app.world.get_mut::<ControlState>(ship).unwrap().advance(ControlFrame { shield: true, ..Default::default() });
+            step_ticks(app, 1);
+        }
+    };
+
+    // Raise the shield, then measure over the following ticks
+    hold_shield(&mut app, 2);
+    assert_eq!(*app.world.get::<CollisionType>(ship).unwrap(), CollisionType::Shield);
+    let energy_before = app.world.get::<Energy>(ship).unwrap().0;
+
+    let ticks = 30;
+    hold_shield(&mut app, ticks);
+
+    let expected = energy_before - SHIELD_ENERGY_DRAIN * ticks as f32 / TICKS_PER_SECOND as f32;
+    let energy = app.world.get::<Energy>(ship).unwrap().0;
+    assert!((energy - expected).abs() < 0.01, "expected {} energy, found {}", expected, energy);
+}
+
+#[test]
+fn shield_drops_when_energy_runs_out() {
+    let mut app = headless_app(0);
+    let ship = start_empty_game(&mut app);
+    app.world.get_mut::<ShipStats>(ship).unwrap().shield_regeneration = 0.0;
+
+    let ticks_to_empty = (100.0 / SHIELD_ENERGY_DRAIN * TICKS_PER_SECOND as f32).ceil() as u64;
+    for _i in 0..ticks_to_empty + 2 {
+        app.world.get_mut::<ControlState>(ship).unwrap().advance(ControlFrame { shield: true, ..Default::default() });
+        step_ticks(&mut app, 1);
+    }
+
+    assert_eq!(*app.world.get::<CollisionType>(ship).unwrap(), CollisionType::Ship);
+}
\ No newline at end of file
@@ -0,0 +1,44 @@
+mod common;
+
+use bevy::prelude::*;
+use cometbuster::{
+    c_chargelevel::ChargeLevel,
+    c_hud::{HudChargeFill, HudEnergyFill, HudStatusText},
+    c_score::Score,
+    c_shipstats::{Energy, ShipStats},
+    c_tags::Player,
+    s_gameplay::GameplayPlugin,
+    s_hud::HudPlugin,
+};
+use common::*;
+
+fn fill_width<T: Component>(app: &mut App) -> Val {
+    let mut query = app.world.query_filtered::<&Style, With<T>>();
+    query.single(&app.world).width
+}
+
+#[test]
+fn hud_follows_the_ship_and_the_score() {
+    let mut app = headless_app_with(0, (GameplayPlugin, HudPlugin));
+    start_game(&mut app);
+    let ship = app.world.query_filtered::<Entity, With<Player>>().single(&app.world);
+    app.world.get_mut::<ShipStats>(ship).unwrap().shield_regeneration = 0.0;
+
+    app.world.get_mut::<Energy>(ship).unwrap().0 = 10.0;
+    app.world.get_mut::<ChargeLevel>(ship).unwrap().0 = 1.5;
+    app.world.resource_mut::<Score>().0 = 120;
+    app.update();
+
+    assert_eq!(fill_width::<HudEnergyFill>(&mut app), Val::Percent(10.0));
+    assert_eq!(fill_width::<HudChargeFill>(&mut app), Val::Percent(75.0));
+    let low_color = app.world.query_filtered::<&BackgroundColor, With<HudEnergyFill>>().single(&app.world).0;
+
+    app.world.get_mut::<Energy>(ship).unwrap().0 = 50.0;
+    app.update();
+    assert_eq!(fill_width::<HudEnergyFill>(&mut app), Val::Percent(50.0));
+    let color = app.world.query_filtered::<&BackgroundColor, With<HudEnergyFill>>().single(&app.world).0;
+    assert_ne!(color, low_color, "the bar should show when there is enough energy for the shield");
+
+    let text = app.world.query_filtered::<&Text, With<HudStatusText>>().single(&app.world);
+    assert!(text.sections[0].value.starts_with("Score 120"), "{}", text.sections[0].value);
+}
\ No newline at end of file
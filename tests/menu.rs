@@ -0,0 +1,166 @@
+mod common;
+
+use bevy::prelude::*;
+use cometbuster::{
+    c_appstate::AppState,
+    c_levels::Wave,
+    c_lifetime_spawntime::SimTick,
+    c_menu::MenuButton,
+    c_sprites::AsteroidSize,
+    c_tags::WaveBannerText,
+    s_gameplay::GameplayPlugin,
+    s_menu::MenuPlugin,
+    s_options::OptionsPlugin,
+    s_pause::PausePlugin,
+    s_wave_banner::WaveBannerPlugin,
+};
+use common::*;
+
+// The headless app with the menus, and a keyboard and gamepad that the test presses itself
+fn menu_app() -> App {
+    let mut app = headless_app_with(0, (GameplayPlugin, MenuPlugin, PausePlugin, OptionsPlugin, WaveBannerPlugin));
+    app
+    .init_resource::<Input<KeyCode>>()
+    .init_resource::<Input<GamepadButton>>()
+    .init_resource::<Axis<GamepadAxis>>()
+    ;
+    app
+}
+
+fn tap_key(app: &mut App, key: KeyCode) {
+    app.world.resource_mut::<Input<KeyCode>>().press(key);
+    app.update();
+    let mut keyboard_input = app.world.resource_mut::<Input<KeyCode>>();
+    keyboard_input.release(key);
+    keyboard_input.clear();
+}
+
+fn tap_button(app: &mut App, button_type: GamepadButtonType) {
+    let button = GamepadButton::new(Gamepad::new(0), button_type);
+    app.world.resource_mut::<Input<GamepadButton>>().press(button);
+    app.update();
+    let mut gamepad_buttons = app.world.resource_mut::<Input<GamepadButton>>();
+    gamepad_buttons.release(button);
+    gamepad_buttons.clear();
+}
+
+fn state(app: &App) -> AppState {
+    app.world.resource::<State<AppState>>().get().clone()
+}
+
+fn go_to(app: &mut App, target: AppState) {
+    for _i in 0..300 {
+        if state(app) == target { return; }
+        app.update();
+    }
+    panic!("never reached {:?}, stuck in {:?}", target, state(app));
+}
+
+fn wave_banners(app: &mut App) -> Vec<Entity> {
+    app.world.query_filtered::<Entity, With<WaveBannerText>>().iter(&app.world).collect()
+}
+
+fn button_labels(app: &mut App) -> Vec<&'static str> {
+    let mut query = app.world.query::<&MenuButton>();
+    let mut buttons: Vec<&MenuButton> = query.iter(&app.world).collect();
+    buttons.sort_by_key(|button| button.index);
+    buttons.iter().map(|button| button.action.label()).collect()
+}
+
+#[test]
+fn title_screen_starts_the_game_and_opens_the_options() {
+    let mut app = menu_app();
+    app.world.resource_mut::<NextState<AppState>>().set(AppState::MainMenu);
+    app.update();
+    app.update();
+    assert_eq!(button_labels(&mut app), vec!["Start", "Options", "Quit"]);
+
+    tap_key(&mut app, KeyCode::Down);
+    tap_key(&mut app, KeyCode::Return);
+    go_to(&mut app, AppState::Options);
+    assert!(button_labels(&mut app).is_empty(), "the title screen should be gone");
+
+    tap_button(&mut app, GamepadButtonType::East);
+    go_to(&mut app, AppState::MainMenu);
+    app.update();
+    tap_button(&mut app, GamepadButtonType::South);
+    go_to(&mut app, AppState::InGame);
+}
+
+#[test]
+fn pause_overlay_freezes_resumes_and_restarts() {
+    let mut app = menu_app();
+    start_game(&mut app);
+    step_ticks(&mut app, 30);
+
+    tap_button(&mut app, GamepadButtonType::Start);
+    go_to(&mut app, AppState::Paused);
+    app.update();
+    assert_eq!(button_labels(&mut app), vec!["Resume", "Restart", "Quit to menu"]);
+    let paused_at = app.world.resource::<SimTick>().0;
+    for _i in 0..10 {
+        app.update();
+    }
+    assert_eq!(app.world.resource::<SimTick>().0, paused_at, "nothing should move while paused");
+
+    tap_key(&mut app, KeyCode::Escape);
+    go_to(&mut app, AppState::InGame);
+    app.update();
+    assert!(app.world.resource::<SimTick>().0 > paused_at);
+    assert!(button_labels(&mut app).is_empty(), "the overlay should be gone");
+
+    tap_key(&mut app, KeyCode::Escape);
+    go_to(&mut app, AppState::Paused);
+    app.update();
+    tap_button(&mut app, GamepadButtonType::DPadDown);
+    tap_button(&mut app, GamepadButtonType::South);
+    go_to(&mut app, AppState::SpawnStart);
+    go_to(&mut app, AppState::InGame);
+}
+
+#[test]
+fn pausing_between_waves_resumes_the_transition() {
+    let mut app = menu_app();
+    start_game(&mut app);
+    let asteroids: Vec<Entity> = app.world.query_filtered::<Entity, With<AsteroidSize>>().iter(&app.world).collect();
+    for asteroid in asteroids {
+        app.world.despawn(asteroid);
+    }
+    go_to(&mut app, AppState::WaveTransition);
+    let banner = wave_banners(&mut app);
+    assert_eq!(banner.len(), 1);
+
+    tap_key(&mut app, KeyCode::Escape);
+    go_to(&mut app, AppState::Paused);
+    let paused_at = app.world.resource::<SimTick>().0;
+    app.update();
+    assert_eq!(app.world.resource::<SimTick>().0, paused_at, "the transition should wait while paused");
+
+    tap_button(&mut app, GamepadButtonType::South);
+    go_to(&mut app, AppState::WaveTransition);
+    assert_eq!(wave_banners(&mut app), banner, "pausing should leave the banner alone");
+    go_to(&mut app, AppState::InGame);
+    assert_eq!(app.world.resource::<Wave>().number, 2);
+    assert!(wave_banners(&mut app).is_empty());
+}
+
+#[test]
+fn quitting_between_waves_takes_down_the_banner() {
+    let mut app = menu_app();
+    start_game(&mut app);
+    let asteroids: Vec<Entity> = app.world.query_filtered::<Entity, With<AsteroidSize>>().iter(&app.world).collect();
+    for asteroid in asteroids {
+        app.world.despawn(asteroid);
+    }
+    go_to(&mut app, AppState::WaveTransition);
+    assert_eq!(wave_banners(&mut app).len(), 1);
+
+    tap_key(&mut app, KeyCode::Escape);
+    go_to(&mut app, AppState::Paused);
+    app.update();
+    tap_button(&mut app, GamepadButtonType::DPadDown);
+    tap_button(&mut app, GamepadButtonType::DPadDown);
+    tap_button(&mut app, GamepadButtonType::South);
+    go_to(&mut app, AppState::MainMenu);
+    assert!(wave_banners(&mut app).is_empty());
+}
\ No newline at end of file
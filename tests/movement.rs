@@ -0,0 +1,69 @@
+mod common;
+
+use bevy::prelude::*;
+use cometbuster::{
+    c_bundles::AsteroidSmallBundle,
+    c_movement_and_collisions::{Angle, AngularVelocity, Velocity},
+    c_playfield::Playfield,
+    c_tags::Original,
+};
+use common::*;
+
+fn assert_inside_playfield(app: &mut App) {
+    let playfield = *app.world.resource::<Playfield>();
+    let mut query = app.world.query_filtered::<&Transform, With<Original>>();
+    for transform in query.iter(&app.world) {
+        let position = transform.translation;
+        assert!(
+            position.x.abs() <= playfield.width / 2.0 && position.y.abs() <= playfield.height / 2.0,
+            "{:?} is outside the playfield", position
+        );
+    }
+}
+
+#[test]
+fn edge_looping_keeps_started_game_inside_playfield() {
+    let mut app = headless_app(1);
+    start_game(&mut app);
+
+    for _i in 0..600 {
+        step_ticks(&mut app, 1);
+        assert_inside_playfield(&mut app);
+    }
+}
+
+#[test]
+fn edge_looping_wraps_fast_bodies_in_every_direction() {
+    let mut app = headless_app(0);
+    start_empty_game(&mut app);
+    // Far enough apart that they never touch, each crossing a different edge or corner
+    for (i, direction) in [
+        Vec2::new(1.0, 0.0), Vec2::new(-1.0, 0.0), Vec2::new(0.0, 1.0), Vec2::new(0.0, -1.0),
+        Vec2::new(1.0, 1.0), Vec2::new(-1.0, -1.0), Vec2::new(1.0, -1.0), Vec2::new(-1.0, 1.0),
+    ].into_iter().enumerate() {
+        let velocity = direction.normalize() * 2000.0;
+        app.world.spawn(AsteroidSmallBundle::default())
+        .insert(Transform::from_xyz(-300.0 + 80.0 * i as f32, 100.0, 10.0))
+        .insert(Velocity { x: velocity.x, y: velocity.y });
+    }
+
+    for _i in 0..120 {
+        step_ticks(&mut app, 1);
+        assert_inside_playfield(&mut app);
+    }
+}
+
+#[test]
+fn spin_turns_asteroids() {
+    let mut app = headless_app(0);
+    start_empty_game(&mut app);
+    let asteroid = app.world.spawn(AsteroidSmallBundle::default())
+    .insert(Angle(0.0))
+    .insert(AngularVelocity(1.0))
+    .id();
+
+    step_ticks(&mut app, 30);
+
+    let angle = app.world.get::<Angle>(asteroid).unwrap().0;
+    assert!(angle > 0.2 && angle < 1.0, "angle {}", angle);
+}
\ No newline at end of file
@@ -0,0 +1,80 @@
+mod common;
+
+use bevy::prelude::*;
+use cometbuster::{
+    c_bundles::{BulletBundle, ShipBundle},
+    c_chargelevel::ChargeLevel,
+    c_controls::{ControlFrame, ControlState},
+    c_movement_and_collisions::Velocity,
+    c_players::{FiredBy, LocalPlayers, PlayerId},
+    c_score::{Kills, Lives},
+    c_settings::Settings,
+    c_tags::{Player, Shield},
+};
+use common::*;
+
+fn ships(app: &mut App) -> Vec<(Entity, PlayerId)> {
+    let mut query = app.world.query_filtered::<(Entity, &PlayerId), With<Player>>();
+    let mut ships: Vec<(Entity, PlayerId)> = query.iter(&app.world).map(|(entity, player_id)| (entity, *player_id)).collect();
+    ships.sort_by_key(|(_, player_id)| *player_id);
+    ships
+}
+
+#[test]
+fn every_local_player_gets_a_ship() {
+    let mut app = headless_app(0);
+    app.insert_resource(LocalPlayers(2));
+    start_game(&mut app);
+
+    let ships = ships(&mut app);
+    assert_eq!(ships.iter().map(|(_, player_id)| *player_id).collect::<Vec<_>>(), vec![PlayerId(0), PlayerId(1)]);
+    let positions: Vec<Vec3> = ships.iter().map(|(ship, _)| app.world.get::<Transform>(*ship).unwrap().translation).collect();
+    assert_ne!(positions[0], positions[1], "the ships should not start on top of each other");
+    let controls = &app.world.resource::<Settings>().controls;
+    assert_ne!(controls[0], controls[1], "each player should have their own bindings");
+    assert_eq!(app.world.resource::<Lives>().remaining, app.world.resource::<Lives>().starting * 2);
+}
+
+#[test]
+fn shooting_another_player_counts_a_kill() {
+    let mut app = headless_app(0);
+    let ship = start_empty_game(&mut app);
+    app.world.resource_mut::<Lives>().remaining = 2;
+    let ship_position = app.world.get::<Transform>(ship).unwrap().translation;
+    app.world.spawn(BulletBundle::default())
+    .insert(Transform::from_xyz(ship_position.x + 200.0, ship_position.y, 10.0))
+    .insert(Velocity { x: -400.0, y: 0.0 })
+    .insert(ChargeLevel(2.0))
+    .insert(FiredBy(PlayerId(1)));
+
+    step_ticks(&mut app, 45);
+
+    assert!(app.world.get_entity(ship).is_none(), "the charged bullet should destroy the ship");
+    assert_eq!(app.world.resource::<Kills>().0, [0, 1, 0, 0]);
+}
+
+#[test]
+fn releasing_the_shield_keeps_the_other_players_shield_up() {
+    let mut app = headless_app(0);
+    app.insert_resource(LocalPlayers(2));
+    let ship_0 = start_empty_game(&mut app);
+    let ship_1 = app.world.spawn(ShipBundle::for_player(PlayerId(1)))
+    .insert(Transform::from_xyz(0.0, 0.0, 20.0))
+    .id();
+
+    for _i in 0..5 {
+        for ship in [ship_0, ship_1] {
+            app.world.get_mut::<ControlState>(ship).unwrap().advance(ControlFrame { shield: true, ..Default::default() });
+        }
+        step_ticks(&mut app, 1);
+    }
+    let mut query_shields = app.world.query_filtered::<&Parent, With<Shield>>();
+    assert_eq!(query_shields.iter(&app.world).count(), 2);
+
+    app.world.get_mut::<ControlState>(ship_0).unwrap().advance(ControlFrame::default());
+    app.world.get_mut::<ControlState>(ship_1).unwrap().advance(ControlFrame { shield: true, ..Default::default() });
+    step_ticks(&mut app, 1);
+
+    let parents: Vec<Entity> = query_shields.iter(&app.world).map(|parent| parent.get()).collect();
+    assert_eq!(parents, vec![ship_1]);
+}
\ No newline at end of file
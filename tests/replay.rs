@@ -0,0 +1,56 @@
+mod common;
+
+use bevy::app::AppExit;
+use cometbuster::{
+    consts::*,
+    c_lifetime_spawntime::SimTick,
+    c_replay::Replay,
+    s_replay::{play_back, record},
+};
+use common::*;
+
+// A file of its own in the temporary directory, for each test
+fn replay_path(name: &str) -> String {
+    std::env::temp_dir().join(format!("cometbuster-{}-{}.ron", name, std::process::id())).to_string_lossy().into_owned()
+}
+
+#[test]
+fn recorded_game_plays_back_to_the_same_world_state() {
+    let path = replay_path("round-trip");
+    let mut recording_app = headless_app(11);
+    record(&mut recording_app, path.clone());
+    start_game(&mut recording_app);
+    let save_ticks = REPLAY_SAVE_SECONDS * TICKS_PER_SECOND;
+    step_scripted_ticks(&mut recording_app, save_ticks + 100);
+
+    // Saved while the game runs, so a crash would not lose it
+    let saved = Replay::load(&path).expect("the recording should be saved before the game exits");
+    assert!(saved.ticks.len() as u64 >= save_ticks, "only {} ticks were saved", saved.ticks.len());
+
+    recording_app.world.send_event(AppExit);
+    advance_scripted_controls(&mut recording_app);
+    recording_app.update();
+    let ticks = recording_app.world.resource::<SimTick>().0;
+    let replay = Replay::load(&path).unwrap();
+    assert_eq!(replay.ticks.len() as u64, ticks, "every tick should be saved on exit");
+
+    // Started with another seed, which the replay replaces with the recorded one
+    let mut playback_app = headless_app(0);
+    play_back(&mut playback_app, replay);
+    start_game(&mut playback_app);
+    let remaining_ticks = ticks - playback_app.world.resource::<SimTick>().0;
+    step_ticks(&mut playback_app, remaining_ticks);
+
+    assert!(world_state(&mut playback_app) == world_state(&mut recording_app), "the replay should end in the recorded world state");
+    std::fs::remove_file(&path).ok();
+}
+
+#[test]
+fn replay_of_another_version_is_refused() {
+    let path = replay_path("version");
+    Replay { version: REPLAY_VERSION + 1, ..Replay::new(0, 1) }.save(&path).unwrap();
+
+    let error = Replay::load(&path).expect_err("a replay of another version should not load");
+    assert!(error.contains("version"), "{}", error);
+    std::fs::remove_file(&path).ok();
+}
\ No newline at end of file
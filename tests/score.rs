@@ -0,0 +1,84 @@
+mod common;
+
+use bevy::prelude::*;
+use cometbuster::{
+    consts::*,
+    c_appstate::AppState,
+    c_bundles::{AsteroidBigBundle, BulletBundle},
+    c_chargelevel::ChargeLevel,
+    c_lifetime_spawntime::ticks_from_seconds,
+    c_movement_and_collisions::{CollisionType, Velocity},
+    c_score::{Lives, Score},
+    c_shipstats::Invulnerable,
+    c_tags::{Player, Shield},
+};
+use common::*;
+
+// A big asteroid flying into the parked ship
+fn ram_ship(app: &mut App, ship: Entity) {
+    let ship_position = app.world.get::<Transform>(ship).unwrap().translation;
+    app.world.spawn(AsteroidBigBundle::default())
+    .insert(Transform::from_xyz(ship_position.x + 200.0, ship_position.y, 10.0))
+    .insert(Velocity { x: -600.0, y: 0.0 });
+}
+
+fn players(app: &mut App) -> Vec<Entity> {
+    let mut query = app.world.query_filtered::<Entity, With<Player>>();
+    query.iter(&app.world).collect()
+}
+
+#[test]
+fn destroyed_asteroids_award_points() {
+    let mut app = headless_app(0);
+    start_empty_game(&mut app);
+    app.world.spawn(AsteroidBigBundle::default())
+    .insert(Transform::from_xyz(0.0, 0.0, 10.0));
+    app.world.spawn(BulletBundle::default())
+    .insert(Transform::from_xyz(-120.0, 0.0, 10.0))
+    .insert(Velocity { x: 400.0, y: 0.0 })
+    .insert(ChargeLevel(2.0));
+
+    step_ticks(&mut app, 15);
+
+    assert_eq!(app.world.resource::<Score>().0, POINTS_ASTEROID_BIG);
+}
+
+#[test]
+fn ship_respawns_after_delay_with_invulnerability() {
+    let mut app = headless_app(0);
+    let ship = start_empty_game(&mut app);
+    app.world.resource_mut::<Lives>().remaining = 2;
+    ram_ship(&mut app, ship);
+
+    step_ticks(&mut app, 30);
+    assert!(players(&mut app).is_empty(), "the ship should be destroyed and waiting to respawn");
+    assert_eq!(app.world.resource::<Lives>().remaining, 1);
+
+    step_ticks(&mut app, ticks_from_seconds(RESPAWN_DELAY_SECONDS));
+    let respawned = players(&mut app);
+    assert_eq!(respawned.len(), 1, "a new ship should have spawned");
+    let new_ship = respawned[0];
+    assert!(app.world.get::<Invulnerable>(new_ship).is_some());
+    assert_eq!(*app.world.get::<CollisionType>(new_ship).unwrap(), CollisionType::Shield);
+
+    step_ticks(&mut app, ticks_from_seconds(INVULNERABILITY_SECONDS) + 1);
+    assert!(app.world.get::<Invulnerable>(new_ship).is_none());
+    assert_eq!(*app.world.get::<CollisionType>(new_ship).unwrap(), CollisionType::Ship);
+    let mut query_shields = app.world.query_filtered::<(), With<Shield>>();
+    assert_eq!(query_shields.iter(&app.world).count(), 0, "the shield should be gone");
+}
+
+#[test]
+fn losing_the_last_ship_ends_the_game() {
+    let mut app = headless_app(0);
+    let ship = start_empty_game(&mut app);
+    app.world.resource_mut::<Lives>().remaining = 1;
+    ram_ship(&mut app, ship);
+
+    for _i in 0..30 {
+        app.update();
+    }
+
+    assert_eq!(*app.world.resource::<State<AppState>>().get(), AppState::GameOver);
+    assert_eq!(app.world.resource::<Lives>().remaining, 0);
+}
\ No newline at end of file
@@ -0,0 +1,51 @@
+use bevy::prelude::*;
+use bevy::window::WindowMode;
+use cometbuster::{
+    consts::*,
+    c_controls::{Action, Binding},
+    c_players::PlayerId,
+    c_settings::Settings,
+};
+
+#[test]
+fn settings_survive_a_round_trip() {
+    let mut settings = Settings {
+        volume: 0.3,
+        window_mode: WindowMode::BorderlessFullscreen,
+        ..Default::default()
+    };
+    settings.controls[2].rebind(Action::Fire, Binding::Key(KeyCode::P));
+    settings.controls[2].rebind(Action::Shield, Binding::Axis(GamepadAxisType::LeftZ, 1.0));
+
+    let loaded = Settings::from_ron(&settings.to_ron().unwrap()).unwrap();
+    assert_eq!(loaded, settings);
+}
+
+#[test]
+fn missing_settings_keep_their_defaults() {
+    let settings = Settings::from_ron("(volume: 2.0, controls: [])").unwrap();
+    assert_eq!(settings.volume, 1.0, "the volume should be clamped");
+    assert_eq!(settings.screen_shake, Settings::default().screen_shake);
+    assert_eq!(settings.controls, Settings::default().controls, "every player should get the default bindings");
+    assert!(Settings::from_ron("(volume: \"loud\")").is_err());
+}
+
+#[test]
+fn conflicts_are_found_across_shared_devices_only() {
+    let settings = Settings::default();
+    for (player, controls) in settings.controls.iter().enumerate().take(MAX_PLAYERS) {
+        for (action, binding) in controls.bindings.iter() {
+            assert_eq!(settings.conflict(PlayerId(player), *action, *binding), None, "the defaults should not conflict");
+        }
+    }
+
+    // Everyone shares the keyboard
+    assert_eq!(settings.conflict(PlayerId(1), Action::Fire, Binding::Key(KeyCode::X)), Some((PlayerId(0), Action::Fire)));
+    // A gamepad belongs to one player, unless another player is set to the same one
+    let south = Binding::Button(GamepadButtonType::South);
+    assert_eq!(settings.conflict(PlayerId(1), Action::Shield, south), Some((PlayerId(1), Action::Fire)));
+    let mut shared = settings.clone();
+    shared.controls[1].gamepad = 0;
+    shared.controls[1].bindings.retain(|(action, _)| *action != Action::Fire);
+    assert_eq!(shared.conflict(PlayerId(1), Action::Shield, south), Some((PlayerId(0), Action::Fire)));
+}
\ No newline at end of file
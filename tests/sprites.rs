@@ -0,0 +1,32 @@
+use bevy::prelude::*;
+use cometbuster::c_sprite_manifest::{SpriteManifest, SpriteVariant};
+
+#[test]
+fn built_in_manifest_has_every_sprite() {
+    let manifest = SpriteManifest::default();
+    for id in SpriteManifest::required_ids() {
+        assert!(!manifest.sprites[&id].variants.is_empty(), "{} has no variants", id);
+    }
+    assert!(manifest.images().contains(&manifest.background));
+}
+
+#[test]
+fn manifest_without_a_required_sprite_is_rejected() {
+    let result = SpriteManifest::from_ron(r#"(
+        background: "textures/background.png",
+        sprites: {
+            "ship": (size: 60.0, z: 20.0, variants: [(image: "textures/ship.png")]),
+        },
+    )"#);
+    assert!(result.is_err());
+}
+
+#[test]
+fn atlas_region_becomes_texture_coordinates() {
+    let variant = SpriteVariant { image: "textures/atlas.png".to_string(), region: Some((256, 0, 256, 128)) };
+    let uv_rect = variant.uv_rect(Vec2::new(1024.0, 512.0));
+    assert_eq!(uv_rect, Rect::new(0.25, 0.0, 0.5, 0.25));
+
+    let whole_image = SpriteVariant { image: "textures/ship.png".to_string(), region: None };
+    assert_eq!(whole_image.uv_rect(Vec2::new(64.0, 64.0)), Rect::new(0.0, 0.0, 1.0, 1.0));
+}
\ No newline at end of file
@@ -0,0 +1,139 @@
+mod common;
+
+use bevy::prelude::*;
+use cometbuster::{
+    consts::*,
+    c_appstate::AppState,
+    c_events::EvRestartWave,
+    c_levels::{Levels, Wave},
+    c_lifetime_spawntime::ticks_from_seconds,
+    c_movement_and_collisions::Velocity,
+    c_playfield::Playfield,
+    c_score::Lives,
+    c_shipstats::{Invulnerable, ShipStats},
+    c_spatial_grid::SpatialGrid,
+    c_sprites::AsteroidSize,
+    c_tags::Player,
+};
+use common::*;
+
+fn asteroid_entities(app: &mut App) -> Vec<Entity> {
+    let mut query = app.world.query_filtered::<Entity, With<AsteroidSize>>();
+    query.iter(&app.world).collect()
+}
+
+fn level_asteroid_count(app: &App, wave: u32) -> usize {
+    let level = app.world.resource::<Levels>().get(wave);
+    level.asteroids.len() + level.random_asteroids.iter().map(|group| group.count as usize).sum::<usize>()
+}
+
+#[test]
+fn first_wave_spawns_the_first_level() {
+    let mut app = headless_app(0);
+    start_game(&mut app);
+
+    assert_eq!(app.world.resource::<Wave>().number, 1);
+    assert_eq!(asteroid_entities(&mut app).len(), level_asteroid_count(&app, 1));
+}
+
+#[test]
+fn clearing_the_field_starts_the_next_wave() {
+    let mut app = headless_app(0);
+    start_game(&mut app);
+
+    for asteroid in asteroid_entities(&mut app) {
+        app.world.despawn(asteroid);
+    }
+    // The state changes on the update after the tick that saw the empty field
+    step_ticks(&mut app, 2);
+    assert_eq!(*app.world.resource::<State<AppState>>().get(), AppState::WaveTransition);
+    assert_eq!(app.world.resource::<Wave>().number, 2);
+
+    step_ticks(&mut app, ticks_from_seconds(WAVE_TRANSITION_SECONDS) + 1);
+    assert_eq!(*app.world.resource::<State<AppState>>().get(), AppState::InGame);
+    assert_eq!(asteroid_entities(&mut app).len(), level_asteroid_count(&app, 2));
+}
+
+#[test]
+fn waves_after_the_last_level_get_faster() {
+    let levels = Levels::default();
+    let last = levels.levels.len() as u32;
+    let last_level = levels.get(last);
+    let next_level = levels.get(last + 1);
+
+    assert_eq!(next_level.asteroids.len(), last_level.asteroids.len());
+    assert!(next_level.speed.1 > last_level.speed.1);
+}
+
+#[test]
+fn restarting_a_wave_spawns_its_level_again() {
+    let mut app = headless_app(0);
+    start_game(&mut app);
+
+    let asteroids = asteroid_entities(&mut app);
+    app.world.despawn(asteroids[0]);
+    app.world.send_event(EvRestartWave);
+    step_ticks(&mut app, 2);
+    assert_eq!(*app.world.resource::<State<AppState>>().get(), AppState::WaveTransition);
+    assert!(asteroid_entities(&mut app).is_empty());
+
+    step_ticks(&mut app, ticks_from_seconds(WAVE_TRANSITION_SECONDS) + 1);
+    assert_eq!(*app.world.resource::<State<AppState>>().get(), AppState::InGame);
+    assert_eq!(app.world.resource::<Wave>().number, 1);
+    assert_eq!(asteroid_entities(&mut app).len(), level_asteroid_count(&app, 1));
+}
+
+#[test]
+fn levels_can_override_ship_stats() {
+    let levels = Levels::default();
+    let level = levels.levels.iter().find(|level| level.ship_stats.turn_rate.is_some()).unwrap();
+    let mut ship_stats = ShipStats::default();
+    level.ship_stats.apply(&mut ship_stats);
+
+    assert_eq!(ship_stats.turn_rate, level.ship_stats.turn_rate.unwrap());
+    assert_eq!(ship_stats.acceleration, ShipStats::default().acceleration);
+}
+
+#[test]
+fn level_files_match_the_built_in_levels() {
+    let from_files: Vec<String> = Levels::load().levels.into_iter().map(|level| level.name).collect();
+    let built_in: Vec<String> = Levels::default().levels.into_iter().map(|level| level.name).collect();
+    assert_eq!(from_files, built_in);
+}
+
+#[test]
+fn levels_can_change_the_playfield() {
+    let mut app = headless_app(0);
+    app.world.resource_mut::<Levels>().levels[0].playfield = Some((1600.0, 900.0));
+    start_game(&mut app);
+
+    assert_eq!(*app.world.resource::<Playfield>(), Playfield::new(1600.0, 900.0));
+    // The collision grid covers the new playfield from the next tick
+    step_ticks(&mut app, 1);
+    assert_eq!(app.world.resource::<SpatialGrid>().playfield, Playfield::new(1600.0, 900.0));
+}
+
+#[test]
+fn ship_parked_on_a_placement_survives_the_start_of_the_wave() {
+    let mut app = headless_app(0);
+    start_game(&mut app);
+    for asteroid in asteroid_entities(&mut app) {
+        app.world.despawn(asteroid);
+    }
+    step_ticks(&mut app, 2);
+    assert_eq!(app.world.resource::<Wave>().number, 2);
+
+    // Right where the next level puts one of its asteroids
+    let placement = app.world.resource::<Levels>().get(2).asteroids[0].position;
+    let ship = app.world.query_filtered::<Entity, With<Player>>().single(&app.world);
+    app.world.entity_mut(ship)
+    .insert(Transform::from_xyz(placement.0, placement.1, 20.0))
+    .insert(Velocity { x: 0.0, y: 0.0 });
+    let lives = app.world.resource::<Lives>().remaining;
+
+    step_ticks(&mut app, ticks_from_seconds(WAVE_TRANSITION_SECONDS) + 30);
+    assert_eq!(*app.world.resource::<State<AppState>>().get(), AppState::InGame);
+    assert!(app.world.get_entity(ship).is_some(), "the ship should survive the asteroid spawning on it");
+    assert!(app.world.get::<Invulnerable>(ship).is_some());
+    assert_eq!(app.world.resource::<Lives>().remaining, lives);
+}
\ No newline at end of file